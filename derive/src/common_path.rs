@@ -18,6 +18,10 @@ pub fn path_is_rc(path: &syn::Path) -> bool {
     path_is(path, "Rc")
 }
 
+pub fn path_is_cell(path: &syn::Path) -> bool {
+    path_is(path, "Cell")
+}
+
 pub fn path_is_float(path: &syn::Path) -> bool {
     path.segments.len() == 1
         && (path_to_string(path) == "Float"
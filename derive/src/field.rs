@@ -42,6 +42,10 @@ pub struct FieldData {
     pub ty: syn::Type,
     pub docs: Option<String>,
     pub api_alias: Option<String>,
+    /// Optional fields wrapped in a `Cell` (i.e., `Cell<Option<T>>`), which
+    /// can be set after the object has been added to the model (e.g., when
+    /// they are calculated from other objects)
+    pub interior: bool,
 }
 
 #[derive(Clone)]
@@ -71,6 +75,7 @@ impl Field {
             ty: ty.clone(),
             docs: None,      // This is nested... the Docs should be in the parent
             api_alias: None, // This is nested... the Docs should be in the parent
+            interior: false,
         };
 
         if let syn::Type::Path(p) = ty {
@@ -123,7 +128,7 @@ impl Field {
 
             let mut api_alias: Option<String> = None;
             for a in attributes.iter() {
-                if a.name == "physical" || a.name == "operational" {
+                if a.name == "physical" || a.name == "operational" || a.name == "personal" {
                     api_alias = a.value.clone();
                     break;
                 }
@@ -140,6 +145,7 @@ impl Field {
                 docs,
                 api_alias,
                 child: None,
+                interior: false,
             };
 
             if path_is_float(&path) {
@@ -154,6 +160,18 @@ impl Field {
                 let ty = extract_type_from_path(&path).unwrap();
                 data.child = Some(Box::new(Self::from_type(&ty)));
                 Self::Option(data)
+            } else if path_is_cell(&path) {
+                // Handled as the Option<T> inside of it
+                let ty = extract_type_from_path(&path).unwrap();
+                match Self::from_type(&ty) {
+                    Self::Option(inner) => {
+                        data.ty = ty;
+                        data.child = inner.child;
+                        data.interior = true;
+                        Self::Option(data)
+                    }
+                    _ => panic!("Only Cell<Option<T>> fields are supported"),
+                }
             } else if path_is_vec(&path) {
                 let ty = extract_type_from_path(&path).unwrap();
                 data.child = Some(Box::new(Self::from_type(&ty)));
@@ -205,6 +223,96 @@ impl Field {
         }
     }
 
+    /// Gets the tokens that transform a borrowed Rust value (i.e., `v`, a
+    /// reference) into a `rhai::Dynamic`. Float, Int, Bool and String become
+    /// the corresponding Rhai values; `Vec` become arrays; objects with an API
+    /// are shared with the script; other objects stored in the model are given
+    /// by name; and other objects are written as in the input files.
+    ///
+    /// Returns `None` for values that cannot be exposed to the API (e.g., `Polygon3D`)
+    pub fn api_value(&self) -> Option<TokenStream2> {
+        match self {
+            Field::Float(_) | Field::Bool(_) => Some(quote!(rhai::Dynamic::from(*v))),
+            Field::Int(_) => Some(quote!(rhai::Dynamic::from(*v as rhai::INT))),
+            Field::String(_) => Some(quote!(rhai::Dynamic::from(v.clone()))),
+            Field::Vec(d) => {
+                let child = d.child.clone().unwrap().api_value()?;
+                Some(quote!(rhai::Dynamic::from(
+                    v.iter().map(|v| #child).collect::<rhai::Array>()
+                )))
+            }
+            Field::Rc(d) => {
+                let child = d.child.clone().unwrap();
+                if let Field::Object(c) = &*child {
+                    if let syn::Type::Path(t) = &c.ty {
+                        if crate::object_has_api(path_to_string(&t.path)) {
+                            return Some(quote!(rhai::Dynamic::from(std::rc::Rc::clone(v))));
+                        }
+                    }
+                }
+                child.api_value()
+            }
+            Field::Object(d) => {
+                if let syn::Type::Path(t) = &d.ty {
+                    let ty_str = path_to_string(&t.path);
+                    if ty_str == "Polygon3D" {
+                        None
+                    } else if ty_str == "Date" {
+                        Some(quote!(rhai::Dynamic::from(vec![
+                            rhai::Dynamic::from(v.month as rhai::INT),
+                            rhai::Dynamic::from(v.day as rhai::INT),
+                        ])))
+                    } else if crate::object_location(ty_str).is_some() {
+                        Some(quote!(rhai::Dynamic::from(v.name().clone())))
+                    } else {
+                        Some(quote!(rhai::Dynamic::from(v.to_spl())))
+                    }
+                } else {
+                    None
+                }
+            }
+            Field::Option(_) | Field::State(_) => None,
+        }
+    }
+
+    /// Gets the tokens that register a read-only getter for a field that is
+    /// not part of the `SimulationState` (e.g., the `max_power` of a `Luminaire`).
+    ///
+    /// Returns `None` if the field cannot be exposed to the API (see `api_value()`)
+    pub fn api_static_getter(&self, object_name: &syn::Ident) -> Option<TokenStream2> {
+        let fieldname = &self.data().ident.unwrap();
+        let api_fieldname = self.api_name();
+
+        if let Field::Option(d) = self {
+            let value = d.child.clone().unwrap().api_value()?;
+            let current = if d.interior {
+                quote!(this.#fieldname.get())
+            } else {
+                quote!(this.#fieldname)
+            };
+            let value_not_available_err = format!(
+                "{} called '{{}}' has not been assigned a value for property '{}'",
+                object_name, api_fieldname
+            );
+            Some(quote!(
+                engine.register_get_result(#api_fieldname, |this: &mut std::rc::Rc<#object_name>| {
+                    match &#current {
+                        Some(v) => Ok(#value),
+                        None => Err(format!(#value_not_available_err, this.name).into()),
+                    }
+                });
+            ))
+        } else {
+            let value = self.api_value()?;
+            Some(quote!(
+                engine.register_get(#api_fieldname, |this: &mut std::rc::Rc<#object_name>| {
+                    let v = &this.#fieldname;
+                    #value
+                });
+            ))
+        }
+    }
+
     pub fn api_getter(&self, object_name: &syn::Ident) -> TokenStream2 {
         let fieldname = &self.data().ident.unwrap();
 
@@ -370,12 +478,16 @@ impl Field {
                     let mut ret_vector = Vec::new();
                     // Check that we are opening an array
                     if field_value.token_type != crate::scanner::TokenType::LeftBracket{
-                        return Err(crate::scanner::make_error_msg(format!("Expecting '[' to open an array ... found '{}'", field_value.token_type), scanner.line))
+                        return Err(crate::SimpleModelError::Syntax{
+                            line: field_value.line,
+                            column: field_value.column,
+                            message: format!("Expecting '[' to open an array ... found '{}'", field_value.token_type),
+                        })
                     }
                     loop {
                         let field_value = scanner.scan_token();
                         match field_value.token_type{
-                            crate::scanner::TokenType::EOF => return Err(crate::scanner::make_error_msg(format!("Unexpected end of file, when reading an Array"), scanner.line)),
+                            crate::scanner::TokenType::EOF => return Err(scanner.syntax_error("Unexpected end of file, when reading an Array".to_string())),
                             crate::scanner::TokenType::RightBracket => {break},
                             _ => {
                                 let element = #resolution;
@@ -387,7 +499,11 @@ impl Field {
                         if should_be_a_comma.token_type == crate::scanner::TokenType::RightBracket{
                             break
                         }else if should_be_a_comma.token_type != crate::scanner::TokenType::Comma{
-                            return Err(crate::scanner::make_error_msg(format!("Elements in a vector must by divided by commas (i.e., ',')... found {}", should_be_a_comma.token_type), scanner.line))
+                            return Err(crate::SimpleModelError::Syntax{
+                                line: should_be_a_comma.line,
+                                column: should_be_a_comma.column,
+                                message: format!("Elements in a vector must by divided by commas (i.e., ',')... found {}", should_be_a_comma.token_type),
+                            })
                         }
                     }
                     // This will remain this size... will it not?
@@ -428,6 +544,8 @@ impl Field {
                     let ty_str = path_to_string(&t.path);
                     if ty_str == "Polygon3D" {
                         resolve_polygon()
+                    } else if ty_str == "Date" {
+                        resolve_date()
                     } else {
                         resolve_other_object(d, &d.ty, parent_is_rc)
                     }
@@ -441,17 +559,73 @@ impl Field {
         }
     }
 
+    /// Gets the tokens that transform a (borrowed) Rust value into a `String`
+    /// that can be read back by [`get_resolution`]. This is, Float, Int
+    /// and Bool are written as they are; Strings are written between quotes;
+    /// objects stored in the model are written by name; `Polygon3D` are written
+    /// as flat arrays of vertices; and all other objects are written inline.
+    pub fn get_writer(&self, value: &TokenStream2) -> TokenStream2 {
+        match self {
+            Field::Float(_) | Field::Int(_) | Field::Bool(_) => {
+                quote!(format!("{}", #value))
+            }
+            Field::String(_) => {
+                quote!(crate::scanner::quote_string(#value.as_str()))
+            }
+            Field::Vec(d) => {
+                let child = d.child.clone().unwrap();
+                let element_writer = child.get_writer(&quote!(element));
+                quote!({
+                    let elements: Vec<String> = #value.iter().map(|element| #element_writer).collect();
+                    format!("[{}]", elements.join(", "))
+                })
+            }
+            Field::Rc(d) | Field::Option(d) => {
+                let child = d.child.clone().unwrap();
+                child.get_writer(value)
+            }
+            Field::Object(d) => {
+                if let syn::Type::Path(t) = &d.ty {
+                    let ty_str = path_to_string(&t.path);
+                    if ty_str == "Polygon3D" {
+                        write_polygon(value)
+                    } else if ty_str == "Date" {
+                        quote!(format!("[{}, {}]", #value.month, #value.day))
+                    } else if crate::object_location(ty_str).is_some() {
+                        // Stored in the model... write a reference by name
+                        quote!(crate::scanner::quote_string(#value.name().as_str()))
+                    } else {
+                        quote!(#value.to_spl())
+                    }
+                } else {
+                    panic!("Werid Object Field")
+                }
+            }
+            Field::State(_d) => {
+                panic!("Trying to write a State field")
+            }
+        }
+    }
+
     /// Gets the tokens that will resolve a Scanner token and transform
     /// it into a Rust value. Float, Int, Bool and String will just be
     /// transformed into their corresponding types, but
     pub fn get_verification(&self, object_name: &syn::Ident) -> TokenStream2 {
         let f_ident = self.data().ident.unwrap();
-        let err_missing = format!("missing required field '{}' on {}", f_ident, object_name);
+        let f_ident_str = format!("{}", f_ident);
+        let object_name_str = format!("{}", object_name);
+        // These errors are reported where the object opens (i.e., its '{')
         let check_mandatory = quote!(
             let #f_ident = match #f_ident{
                 Some(v)=>v,
                 None => {
-                    return Err(crate::scanner::make_error_msg(format!(#err_missing), scanner.line))
+                    return Err(crate::SimpleModelError::MissingField{
+                        line: left_brace.line,
+                        column: left_brace.column,
+                        object_type: #object_name_str.to_string(),
+                        object_name: object_name_for_errors,
+                        field: #f_ident_str.to_string(),
+                    })
                 }
             };
         );
@@ -463,10 +637,15 @@ impl Field {
 
             Field::Vec(d) => {
                 let f_ident = d.ident.clone().unwrap();
-                let err_empty = format!("empty field '{}' on {}", f_ident, object_name);
                 quote!(
                     if #f_ident.is_empty() {
-                        return Err(crate::scanner::make_error_msg(format!(#err_empty), scanner.line))
+                        return Err(crate::SimpleModelError::EmptyField{
+                            line: left_brace.line,
+                            column: left_brace.column,
+                            object_type: #object_name_str.to_string(),
+                            object_name: object_name_for_errors,
+                            field: #f_ident_str.to_string(),
+                        })
                     }
                 )
             }
@@ -499,7 +678,12 @@ impl Field {
             }
             Field::Object(d) => {
                 if let syn::Type::Path(t) = &d.ty {
-                    path_to_string(&t.path)
+                    let ty_str = path_to_string(&t.path);
+                    if ty_str == "Date" {
+                        "[month, day]".to_string()
+                    } else {
+                        ty_str
+                    }
                 } else {
                     panic!("Weird object when getting docs")
                 }
@@ -549,7 +733,7 @@ fn resolve_other_object(d: &FieldData, objtype: &syn::Type, parent_is_rc: bool)
         syn::Type::Path(p) => path_to_string(&p.path),
         _ => panic!("Expecting Path in resolve_other_object"),
     };
-    let incorrect_type_msg = format!("expecting '{}', found '{{}}'", ty_str);
+    let expected_type = format!("'{}'", ty_str);
 
     // This creates a new object from scratch
     let create_other_object = quote!(
@@ -558,26 +742,44 @@ fn resolve_other_object(d: &FieldData, objtype: &syn::Type, parent_is_rc: bool)
             // Check that it is the same kind of object
             let found_id = std::str::from_utf8(field_value.txt).unwrap();
             if #ty_str != found_id {
-                return Err(crate::scanner::make_error_msg(format!(#incorrect_type_msg, found_id), scanner.line))
+                return Err(crate::SimpleModelError::InvalidValue{
+                    line: field_value.line,
+                    column: field_value.column,
+                    expected: #expected_type.to_string(),
+                    found: format!("'{}'", found_id),
+                    object_type: None,
+                    object_name: None,
+                    field: None,
+                })
             }
             // Setup scanner
             scanner.update_start_index();
+            let object_line = scanner.line;
             let (start,end) = scanner.get_object_slice();
             let these_bytes = scanner.borrow_slice(start, end);
             // Scan
-            let aux = #objtype::from_bytes(scanner.line, these_bytes, model)?;
+            let aux = #objtype::from_bytes(object_line, these_bytes, model)
+                .map_err(|e| e.shift_column(object_line, scanner.column(start) - 1))?;
             // return Wrapped in Rc, or not...
             #final_wrapping
         } else {
             // return error
-            return Err(crate::scanner::make_error_msg(format!("Expecting an object name (i.e., String) or another Object... found '{}'",field_value.token_type), scanner.line));
+            return Err(crate::SimpleModelError::InvalidValue{
+                line: field_value.line,
+                column: field_value.column,
+                expected: "an object name (i.e., String) or another Object".to_string(),
+                found: format!("'{}'", field_value.token_type),
+                object_type: None,
+                object_name: None,
+                field: None,
+            });
         }
     );
 
     if let syn::Type::Path(p) = &d.ty {
         if let Some(target_vec) = crate::object_location(ty_str) {
             let vec_ident = syn::Ident::new(target_vec, proc_macro2::Span::call_site());
-            let err_not_found = format!("{} called '{{}}' not found", path_to_string(&p.path));
+            let referenced_type = path_to_string(&p.path);
 
             quote!(
                 // If we find a String, then we search in the Model
@@ -598,7 +800,15 @@ fn resolve_other_object(d: &FieldData, objtype: &syn::Type, parent_is_rc: bool)
 
                     // If not found... complain
                     if aux.is_none(){
-                        return Err(crate::scanner::make_error_msg(format!(#err_not_found, aux_name), scanner.line))
+                        return Err(crate::SimpleModelError::UnknownReference{
+                            line: field_value.line,
+                            column: field_value.column,
+                            reference_type: #referenced_type.to_string(),
+                            reference_name: aux_name,
+                            object_type: None,
+                            object_name: None,
+                            field: None,
+                        })
                     }
                     // otherwise, return the object itself (unwraped)
                     aux.unwrap()
@@ -622,23 +832,31 @@ fn resolve_polygon() -> TokenStream2 {
         let mut the_vector = Vec::new();
         // Check that we are opening an array
         if field_value.token_type != crate::scanner::TokenType::LeftBracket {
-            return Err(crate::scanner::make_error_msg(
-                format!(
+            return Err(crate::SimpleModelError::Syntax {
+                line: field_value.line,
+                column: field_value.column,
+                message: format!(
                     "Expecting '[' to open a Polygon... found '{}'",
                     field_value.token_type
                 ),
-                scanner.line,
-            ));
+            });
         }
+        // Geometric errors are reported where the Polygon starts
+        let geometry_error = move |message: String| crate::SimpleModelError::InvalidGeometry {
+            line: field_value.line,
+            column: field_value.column,
+            message,
+        };
         // Loop through the whole array
         loop {
             let field_value = scanner.scan_token();
             match field_value.token_type {
                 crate::scanner::TokenType::EOF | crate::scanner::TokenType::RightBrace => {
-                    return Err(crate::scanner::make_error_msg(
-                        format!("Polygon definition does not end."),
-                        scanner.line,
-                    ))
+                    return Err(crate::SimpleModelError::Syntax {
+                        line: field_value.line,
+                        column: field_value.column,
+                        message: "Polygon definition does not end.".to_string(),
+                    })
                 }
                 crate::scanner::TokenType::RightBracket => break,
                 _ => {
@@ -652,23 +870,27 @@ fn resolve_polygon() -> TokenStream2 {
             if comma.token_type == crate::scanner::TokenType::RightBracket {
                 break;
             } else if comma.token_type != crate::scanner::TokenType::Comma {
-                return Err(crate::scanner::make_error_msg(
-                    format!(
+                return Err(crate::SimpleModelError::Syntax {
+                    line: comma.line,
+                    column: comma.column,
+                    message: format!(
                         "Elements in a vector must by divided by commas (i.e., ',')... found {}",
                         comma.token_type
                     ),
-                    scanner.line,
-                ));
+                });
             }
         }
 
         // Check coherent input
         let n_numbers = the_vector.len();
         if n_numbers % 3 != 0 {
-            return Err(crate::scanner::make_error_msg(format!("The length of the vector defining a Polygon must be divisible by 3... found {}", n_numbers ), scanner.line));
+            return Err(geometry_error(format!(
+                "The length of the vector defining a Polygon must be divisible by 3... found {}",
+                n_numbers
+            )));
         }
         if n_numbers < 9 {
-            return Err(crate::scanner::make_error_msg(format!("The length of the vector defining a Polygon must be at least 9 (three 3D vertices)... found {}", n_numbers ), scanner.line));
+            return Err(geometry_error(format!("The length of the vector defining a Polygon must be at least 9 (three 3D vertices)... found {}", n_numbers )));
         }
 
         // Build the polygon
@@ -679,12 +901,83 @@ fn resolve_polygon() -> TokenStream2 {
             let x = the_vector[first];
             let y = the_vector[first + 1];
             let z = the_vector[first + 2];
-            the_loop.push(geometry3d::Point3D::new(x, y, z))?;
+            the_loop
+                .push(geometry3d::Point3D::new(x, y, z))
+                .map_err(geometry_error)?;
         }
         if let Err(_) = the_loop.close() {
-            return Err("It seems that some of the vertices in the surface are collinear. You do not have the minimum of 3 non-collinear vertices".to_string());
+            return Err(geometry_error("It seems that some of the vertices in the surface are collinear. You do not have the minimum of 3 non-collinear vertices".to_string()));
         }
-        let p = Polygon3D::new(the_loop)?;
+        let p = Polygon3D::new(the_loop).map_err(geometry_error)?;
         p
     })
 }
+
+fn resolve_date() -> TokenStream2 {
+    quote!({
+        // Dates are given as [month, day]
+        let invalid =
+            |line: usize, column: usize, found: String| crate::SimpleModelError::InvalidValue {
+                line,
+                column,
+                expected: "a date given as [month, day] (e.g., [12, 25])".to_string(),
+                found,
+                object_type: None,
+                object_name: None,
+                field: None,
+            };
+        if field_value.token_type != crate::scanner::TokenType::LeftBracket {
+            return Err(invalid(
+                field_value.line,
+                field_value.column,
+                format!("{}", field_value.token_type),
+            ));
+        }
+        let mut month_day = Vec::with_capacity(2);
+        for expected in [
+            crate::scanner::TokenType::Comma,
+            crate::scanner::TokenType::RightBracket,
+        ] {
+            let number = scanner.scan_token();
+            let value = number.resolve_as_usize()?;
+            month_day.push((value, number.line, number.column));
+            let delimiter = scanner.scan_token();
+            if delimiter.token_type != expected {
+                return Err(invalid(
+                    delimiter.line,
+                    delimiter.column,
+                    format!("{}", delimiter.token_type),
+                ));
+            }
+        }
+        let (month, month_line, month_column) = month_day[0];
+        let (day, day_line, day_column) = month_day[1];
+        if !(1..=12).contains(&month) {
+            return Err(invalid(
+                month_line,
+                month_column,
+                format!("month {}", month),
+            ));
+        }
+        if !(1..=31).contains(&day) {
+            return Err(invalid(day_line, day_column, format!("day {}", day)));
+        }
+        calendar::Date {
+            month: month as u8,
+            day: day as u8,
+            hour: 0.,
+        }
+    })
+}
+
+fn write_polygon(value: &TokenStream2) -> TokenStream2 {
+    quote!({
+        let coordinates: Vec<String> = #value
+            .outer()
+            .vertices()
+            .iter()
+            .map(|p| format!("{}, {}, {}", p.x, p.y, p.z))
+            .collect();
+        format!("[\n        {}\n    ]", coordinates.join(",\n        "))
+    })
+}
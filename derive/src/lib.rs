@@ -28,6 +28,13 @@ fn object_location(typename: String) -> Option<&'static str> {
         ("Fenestration", "fenestrations"),
         ("HVAC", "hvacs"),
         ("Luminaire", "luminaires"),
+        ("ShadingDevice", "shading_devices"),
+        ("Schedule", "schedules"),
+        ("Occupant", "occupants"),
+        ("Equipment", "equipment"),
+        ("Thermostat", "thermostats"),
+        ("Ventilation", "ventilations"),
+        ("AirExchange", "air_exchanges"),
     ]);
 
     if let Some(v) = mapping.get(&typename.as_str()) {
@@ -41,7 +48,17 @@ fn object_has_api(typename: String) -> bool {
     let typename_bytes = typename.as_bytes();
     matches!(
         typename_bytes,
-        b"Space" | b"Surface" | b"Fenestration" | b"HVAC" | b"Luminaire"
+        b"Space"
+            | b"Surface"
+            | b"Fenestration"
+            | b"HVAC"
+            | b"Luminaire"
+            | b"ShadingDevice"
+            | b"Occupant"
+            | b"Equipment"
+            | b"Thermostat"
+            | b"Ventilation"
+            | b"AirExchange"
     )
 }
 
@@ -77,12 +94,16 @@ pub fn derive_simulation_state_behaviour(input: TokenStream) -> TokenStream {
                 }
             };
 
+            let derive_encoding = derive_enum_encoding(&ast, &variants);
+
             // Gather everything
             TokenStream::from(quote!(
                 impl #enum_name {
 
 
                     #derive_kind_variants
+
+                    #derive_encoding
                 }
             ))
         }
@@ -104,6 +125,14 @@ pub fn derive_input_output(input: TokenStream) -> TokenStream {
     // From Bytes
     let from_bytes = obj.gen_from_bytes();
 
+    // To SPL... the SimpleModel is not written as a single
+    // object but as the list of objects it contains (see `SimpleModel::to_spl()`)
+    let to_spl = if name_str == "SimpleModel" {
+        quote!()
+    } else {
+        obj.gen_to_spl()
+    };
+
     // New
     let new = obj.gen_new();
 
@@ -123,6 +152,8 @@ pub fn derive_input_output(input: TokenStream) -> TokenStream {
 
             #from_bytes
 
+            #to_spl
+
             # docs
 
             /// Retrieves the type of object as a `&'static str`.
@@ -154,7 +185,7 @@ pub fn derive_group_input_output(input: TokenStream) -> TokenStream {
     TokenStream::from(q)
 }
 
-#[proc_macro_derive(ObjectAPI, attributes(operational, physical))]
+#[proc_macro_derive(ObjectAPI, attributes(operational, physical, personal, api_methods))]
 pub fn derive_object_api(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let docs = get_docs(&ast.attrs);
@@ -170,7 +201,10 @@ pub fn derive_group_api(input: TokenStream) -> TokenStream {
     TokenStream::from(obj.gen_group_api())
 }
 
-#[proc_macro_derive(GroupMemberAPI, attributes(operational, physical))]
+#[proc_macro_derive(
+    GroupMemberAPI,
+    attributes(operational, physical, personal, api_methods)
+)]
 pub fn derive_group_member_api(input: TokenStream) -> TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
     let docs = get_docs(&ast.attrs);
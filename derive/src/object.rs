@@ -16,7 +16,7 @@ impl Object {
     pub fn new(ast: syn::DeriveInput, docs: String) -> Self {
         match ast.data {
             syn::Data::Struct(stru) => {
-                let data = StructObject::new(ast.ident.clone(), stru, docs);
+                let data = StructObject::new(ast.ident.clone(), stru, &ast.attrs, docs);
                 Object::StructObject(data)
             }
             syn::Data::Enum(stru) => {
@@ -50,6 +50,13 @@ impl Object {
         }
     }
 
+    pub fn gen_to_spl(&self) -> TokenStream2 {
+        match self {
+            Self::StructObject(s) => s.gen_to_spl(),
+            Self::Enum(s) => s.gen_to_spl(),
+        }
+    }
+
     pub fn gen_new(&self) -> TokenStream2 {
         match self {
             Self::StructObject(s) => s.gen_new(),
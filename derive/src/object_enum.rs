@@ -77,7 +77,11 @@ impl Variant {
                 let mut in_match = quote!(
                     let left_paren = scanner.scan_token();
                     if left_paren.token_type != crate::scanner::TokenType::LeftParen{
-                        return Err(crate::scanner::make_error_msg(format!("Expecting '(' when opening fields in Enum... found '{}'", left_paren.token_type), scanner.line));
+                        return Err(crate::SimpleModelError::Syntax{
+                            line: left_paren.line,
+                            column: left_paren.column,
+                            message: format!("Expecting '(' when opening fields in Enum... found '{}'", left_paren.token_type),
+                        });
                     }
                 );
                 // resolve each field
@@ -87,6 +91,18 @@ impl Variant {
                     let this_resol = f.get_resolution(&field_value, false);
                     let this_iden =
                         syn::Ident::new(&format!("field{}", i), proc_macro2::Span::call_site());
+                    if i > 0 {
+                        // Fields can be separated by commas (which is how they
+                        // are written), but older files might not have them
+                        in_match = quote!(
+                            #in_match
+
+                            scanner.skip_white_space();
+                            if scanner.peek() == ',' {
+                                scanner.scan_token();
+                            }
+                        );
+                    }
                     in_match = quote!(
                         #in_match
 
@@ -103,8 +119,13 @@ impl Variant {
                 in_match = quote!(
                     #in_match
 
-                    if scanner.scan_token().token_type != crate::scanner::TokenType::RightParen{
-                        return Err(format!("Expecting ')' when closing fields in Enum"));
+                    let right_paren = scanner.scan_token();
+                    if right_paren.token_type != crate::scanner::TokenType::RightParen{
+                        return Err(crate::SimpleModelError::Syntax{
+                            line: right_paren.line,
+                            column: right_paren.column,
+                            message: format!("Expecting ')' when closing fields in Enum... found '{}'", right_paren.token_type),
+                        });
                     }
 
                     #object_name::#self_ident(#fieldlist)
@@ -120,6 +141,46 @@ impl Variant {
             }
         }
     }
+
+    /// Gets the match arm that writes this variant in the
+    /// same format it is read (e.g., `Infiltration::Constant(2.2)`)
+    fn write(&self, object_name: &syn::Ident) -> TokenStream2 {
+        let self_ident = self.data().ident;
+        let prefix = format!("{}::{}", object_name, self_ident);
+        match self {
+            Self::Unit(_) => {
+                // The parentheses are needed for knowing where the object ends
+                quote!(
+                    #object_name::#self_ident => format!("{}()", #prefix),
+                )
+            }
+            Self::Unnamed(data) => {
+                let mut fieldlist = quote!();
+                let mut writers = quote!();
+                for (i, f) in data.fields.iter().enumerate() {
+                    let this_iden =
+                        syn::Ident::new(&format!("field{}", i), proc_macro2::Span::call_site());
+                    let writer = f.get_writer(&quote!(#this_iden));
+                    if i == 0 {
+                        fieldlist = quote!(#this_iden);
+                    } else {
+                        fieldlist = quote!(#fieldlist,#this_iden);
+                    }
+                    writers = quote!(
+                        #writers
+                        values.push(#writer);
+                    );
+                }
+                quote!(
+                    #object_name::#self_ident(#fieldlist) => {
+                        let mut values: Vec<String> = Vec::new();
+                        #writers
+                        format!("{}({})", #prefix, values.join(", "))
+                    },
+                )
+            }
+        }
+    }
 }
 
 pub struct EnumObject {
@@ -148,18 +209,26 @@ impl EnumObject {
             let mut scanner = crate::scanner::SimpleScanner::new(bytes, line);
             let colon_colon = scanner.scan_token();
             if colon_colon.token_type != crate::scanner::TokenType::ColonColon{
-                return Err(crate::scanner::make_error_msg(format!(" Expecting '::' after EnumName, found {} '{}'", colon_colon.token_type, std::str::from_utf8(colon_colon.txt).unwrap()), scanner.line))
+                return Err(crate::SimpleModelError::Syntax{
+                    line: colon_colon.line,
+                    column: colon_colon.column,
+                    message: format!("Expecting '::' after EnumName, found {} '{}'", colon_colon.token_type, std::str::from_utf8(colon_colon.txt).unwrap()),
+                })
             }
             let obj_name = scanner.scan_token();
             if obj_name.token_type != crate::scanner::TokenType::Identifier{
-                return Err(crate::scanner::make_error_msg(format!(" Expecting 'Identifier' after '::', found {} '{}'", obj_name.token_type, std::str::from_utf8(obj_name.txt).unwrap()), scanner.line))
+                return Err(crate::SimpleModelError::Syntax{
+                    line: obj_name.line,
+                    column: obj_name.column,
+                    message: format!("Expecting 'Identifier' after '::', found {} '{}'", obj_name.token_type, std::str::from_utf8(obj_name.txt).unwrap()),
+                })
             }
         );
 
         let object_name = &self.ident;
 
         let mut match_statement = quote!();
-        let unexpected_field_err = format!("Object '{{}}' is not in enum '{}'", object_name);
+        let object_name_str = format!("{}", object_name);
 
         for var in &self.variants {
             let this_resolve = var.resolve(object_name);
@@ -173,8 +242,12 @@ impl EnumObject {
         match_statement = quote!(
             #match_statement
             _ => {
-                let obj_name = std::str::from_utf8(obj_name.txt).unwrap();
-                return Err(crate::scanner::make_error_msg(format!(#unexpected_field_err, obj_name), scanner.line))
+                let obj_name_str = std::str::from_utf8(obj_name.txt).unwrap();
+                return Err(crate::SimpleModelError::UnknownObjectType{
+                    line: obj_name.line,
+                    column: obj_name.column,
+                    object_type: format!("{}::{}", #object_name_str, obj_name_str),
+                })
             },
         );
 
@@ -184,7 +257,7 @@ impl EnumObject {
         );
         quote!(
             #[doc = #from_bytes_docstring]
-            pub fn from_bytes(line: usize, bytes: &[u8], model: &crate::model::SimpleModel)->Result<Self, String>{
+            pub fn from_bytes(line: usize, bytes: &[u8], model: &crate::model::SimpleModel)->Result<Self, crate::SimpleModelError>{
                 #ret
 
                 let obj_name_str = std::str::from_utf8(obj_name.txt).unwrap();
@@ -196,6 +269,32 @@ impl EnumObject {
         )
     }
 
+    pub fn gen_to_spl(&self) -> TokenStream2 {
+        let object_name = &self.ident;
+        let mut match_statement = quote!();
+        for var in &self.variants {
+            let this_write = var.write(object_name);
+            match_statement = quote!(
+                #match_statement
+
+                #this_write
+            )
+        }
+
+        let to_spl_docstring = format!(
+            " Writes the [`{}`] in the same format that is read by `from_bytes()`",
+            object_name
+        );
+        quote!(
+            #[doc = #to_spl_docstring]
+            pub fn to_spl(&self) -> String {
+                match self {
+                    #match_statement
+                }
+            }
+        )
+    }
+
     pub fn gen_docs(&self) -> String {
         let mut ret = String::new();
 
@@ -252,6 +351,7 @@ impl EnumObject {
 
         let mut from_bytes = quote!();
         let mut name_fn = quote!();
+        let mut to_spl_fn = quote!();
 
         let mut doc_string = format!("# {}\n\n{}\n\n", object_name, object_docs);
 
@@ -268,13 +368,23 @@ impl EnumObject {
                 }
             );
 
+            let prefix = format!("{}::{}", object_name, ident);
+            to_spl_fn = quote!(
+                #to_spl_fn
+
+                #object_name::#ident(o) => {
+                    format!("{} {}", #prefix, o.to_spl_body())
+                }
+            );
+
             // Extend from_bytes() match statement
             from_bytes = quote!(
                 #from_bytes
 
                 #ident_str => {
                     // println!("Variant is {}", #ident);
-                    let ret = #ident::from_bytes(scanner.line, slice, model)?;
+                    let ret = #ident::from_bytes(slice_line, slice, model)
+                        .map_err(|e| e.shift_column(slice_line, scanner.column(start) - 1))?;
                     Ok(Self::#ident(std::rc::Rc::new(ret)))
                 }
             );
@@ -291,21 +401,31 @@ impl EnumObject {
 
         // From bytes
         from_bytes = quote!(
-            pub fn from_bytes(line: usize, bytes: &[u8], model: &SimpleModel)->Result<#object_name, String>{
+            pub fn from_bytes(line: usize, bytes: &[u8], model: &SimpleModel)->Result<#object_name, crate::SimpleModelError>{
                 let mut scanner = crate::scanner::SimpleScanner::new(bytes, line);
                 let colon_colon = scanner.scan_token();
                 if colon_colon.token_type != crate::scanner::TokenType::ColonColon{
-                    return Err(crate::scanner::make_error_msg(format!(#err_colon_colon), scanner.line));
+                    return Err(crate::SimpleModelError::Syntax{
+                        line: colon_colon.line,
+                        column: colon_colon.column,
+                        message: format!(#err_colon_colon),
+                    });
                 }
-                let kind = scanner.scan_token();
-                let kind = std::str::from_utf8(kind.txt).unwrap();
+                let kind_token = scanner.scan_token();
+                let kind = std::str::from_utf8(kind_token.txt).unwrap();
                 scanner.update_start_index();
+                // The line in which the object starts
+                let slice_line = scanner.line;
                 let (start,end) = scanner.get_object_slice();
                 let slice = scanner.borrow_slice(start, end);
                 match kind{
                     #from_bytes
                     _ => {
-                        Err(crate::scanner::make_error_msg(format!("Unknown kind of HVAC '{}'", kind), scanner.line))
+                        Err(crate::SimpleModelError::UnknownObjectType{
+                            line: kind_token.line,
+                            column: kind_token.column,
+                            object_type: format!("{}::{}", #object_name_str, kind),
+                        })
                     }
                 }
             }
@@ -320,6 +440,15 @@ impl EnumObject {
             }
         );
 
+        to_spl_fn = quote!(
+            /// Writes the object in the same format that is read by `from_bytes()`
+            pub fn to_spl(&self) -> String {
+                match self {
+                    #to_spl_fn
+                }
+            }
+        );
+
         let print_doc = quote!(
 
             #[cfg(debug_assertions)]
@@ -343,6 +472,8 @@ impl EnumObject {
 
                 #name_fn
 
+                #to_spl_fn
+
                 #print_doc
             }
         )
@@ -360,6 +491,7 @@ impl EnumObject {
             )
         });
         let location = syn::Ident::new(location_str, proc_macro2::Span::call_site());
+        let count_fn_name = format!("n_{}", location_str);
         let not_found_err = format!("Could not find {} '{{}}'", &object_name);
         let negative_index_err = format!(
             "Impossible to get {} using a negative index ({{}} was given)",
@@ -372,6 +504,7 @@ impl EnumObject {
 
         let mut name_match_statement = quote!();
         let mut index_match_statement = quote!();
+        let mut dynamic_match_statement = quote!();
 
         for v in self.variants.iter() {
             let v_ident = v.data().ident.clone();
@@ -387,6 +520,11 @@ impl EnumObject {
                 }
             );
 
+            dynamic_match_statement = quote!(
+                #dynamic_match_statement
+                #object_name::#v_ident(s) => rhai::Dynamic::from(std::rc::Rc::clone(s)),
+            );
+
             index_match_statement = quote!(
                 #index_match_statement
 
@@ -398,10 +536,32 @@ impl EnumObject {
             )
         }
 
+        let to_dynamic_docstring = format!(
+            " Wraps the object within this [`{}`] into a `rhai::Dynamic`",
+            &object_name
+        );
+
         quote!(
             impl #object_name {
+                #[doc = #to_dynamic_docstring]
+                pub fn to_dynamic(&self) -> rhai::Dynamic {
+                    match self {
+                        #dynamic_match_statement
+                    }
+                }
+
                 pub fn register_api(engine : &mut rhai::Engine, model: &std::rc::Rc<SimpleModel>, state: &std::rc::Rc<std::cell::RefCell<crate::SimulationState>>, research_mode: bool){
 
+                    // All of them
+                    let new_mod = std::rc::Rc::clone(model);
+                    engine.register_fn(#location_str, move || -> rhai::Array {
+                        new_mod.#location.iter().map(|s| s.to_dynamic()).collect()
+                    });
+
+                    // How many
+                    let new_mod = std::rc::Rc::clone(model);
+                    engine.register_fn(#count_fn_name, move || new_mod.#location.len() as rhai::INT);
+
                     // By name
                     let new_mod = std::rc::Rc::clone(model);
                     engine.register_result_fn(#name_str_lower, move |name: &str | {
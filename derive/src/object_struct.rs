@@ -7,12 +7,20 @@ pub struct StructObject {
     pub ident: syn::Ident,
     fields: Vec<Field>,
     docs: String,
+    /// The methods exposed to the API as read-only properties, given in
+    /// an `#[api_methods(...)]` attribute, and whether they receive the model
+    api_methods: Vec<(syn::Ident, bool)>,
     // optional_fields: Vec<Field>,
     // state_fields: Vec<Field>,
 }
 
 impl StructObject {
-    pub fn new(ident: syn::Ident, stru: syn::DataStruct, docs: String) -> Self {
+    pub fn new(
+        ident: syn::Ident,
+        stru: syn::DataStruct,
+        attrs: &[syn::Attribute],
+        docs: String,
+    ) -> Self {
         let fields: Vec<Field> = Self::get_object_fields(&stru)
             .into_iter()
             .map(Field::new)
@@ -22,9 +30,36 @@ impl StructObject {
             ident,
             fields,
             docs,
+            api_methods: Self::get_api_methods(attrs),
         }
     }
 
+    /// Reads the methods listed in the `#[api_methods(...)]` attribute
+    /// (e.g., `#[api_methods(area, floor_area(model))]`). Methods followed by
+    /// `(model)` receive a reference to the `SimpleModel`.
+    fn get_api_methods(attrs: &[syn::Attribute]) -> Vec<(syn::Ident, bool)> {
+        let mut ret: Vec<(syn::Ident, bool)> = Vec::new();
+        for a in attrs.iter() {
+            if a.path.segments[0].ident != "api_methods" {
+                continue;
+            }
+            a.tokens.clone().into_iter().for_each(|token| {
+                if let proc_macro2::TokenTree::Group(g) = token {
+                    g.stream().into_iter().for_each(|t| match t {
+                        proc_macro2::TokenTree::Ident(i) => ret.push((i, false)),
+                        proc_macro2::TokenTree::Group(_) => {
+                            if let Some(last) = ret.last_mut() {
+                                last.1 = true;
+                            }
+                        }
+                        _ => {}
+                    })
+                }
+            });
+        }
+        ret
+    }
+
     pub fn gen_docs(&self) -> String {
         let mut ret = String::new();
 
@@ -84,13 +119,29 @@ impl StructObject {
 
     pub fn gen_from_bytes(&self) -> TokenStream2 {
         let object_name = &self.ident;
+        let object_name_str = format!("{}", self.ident);
+
+        // Errors will report the name of the object, if given
+        let has_name = self
+            .fields
+            .iter()
+            .any(|f| !matches!(f, Field::State(_)) && f.data().ident.unwrap() == "name");
+        let object_name_for_errors = if has_name {
+            quote!(name.clone())
+        } else {
+            quote!(None)
+        };
 
         // Create scanner.
         let mut from_bytes = quote!(
             let mut scanner = crate::scanner::SimpleScanner::new(bytes, line);
             let left_brace = scanner.scan_token();
             if left_brace.token_type != crate::scanner::TokenType::LeftBrace{
-                return Err(crate::scanner::make_error_msg(format!(" Expecting left brace ('{{') when opening Object, found '{}' ({})", left_brace.token_type, std::str::from_utf8(left_brace.txt).unwrap()), scanner.line))
+                return Err(crate::SimpleModelError::Syntax{
+                    line: left_brace.line,
+                    column: left_brace.column,
+                    message: format!("Expecting left brace ('{{') when opening Object, found '{}' ({})", left_brace.token_type, std::str::from_utf8(left_brace.txt).unwrap()),
+                })
             }
         );
 
@@ -132,12 +183,19 @@ impl StructObject {
             }
 
             let resolution = f.get_resolution(&field_value, false);
+            // Errors in values report the object and field being read
+            let resolution = quote!(
+                #[allow(clippy::needless_question_mark)]
+                let resolved = (|| Ok::<_, crate::SimpleModelError>(#resolution))();
+                let resolved = resolved.map_err(|e| e.in_field(#object_name_str, &#object_name_for_errors, #field_name))?;
+            );
             if let Field::Vec(_) = f {
                 the_match_content = quote!(
                     #the_match_content
 
                     #field_name => {
-                        #f_ident = #resolution;
+                        #resolution
+                        #f_ident = resolved;
                     },
                 );
             } else {
@@ -145,18 +203,22 @@ impl StructObject {
                     #the_match_content
 
                     #field_name => {
-                        #f_ident = Some(#resolution);
+                        #resolution
+                        #f_ident = Some(resolved);
                     },
                 );
             }
         }
 
-        let unexpected_field_err = format!("unexpected field '{{}}' in object '{}'", self.ident);
-
         the_match_content = quote!(
             #the_match_content
             _ => {
-                return Err(crate::scanner::make_error_msg(format!(#unexpected_field_err, txt_str), scanner.line))
+                return Err(crate::SimpleModelError::UnknownField{
+                    line: field_name.line,
+                    column: field_name.column,
+                    object_type: #object_name_str.to_string(),
+                    field: txt_str.to_string(),
+                })
             },
         );
 
@@ -181,9 +243,9 @@ impl StructObject {
                     },// no problem with this.
                     '}' => {break},// we are done
                     '{' => {},// don't do anything... we need to scan that object.
-                    '\0' => {return Err(crate::scanner::make_error_msg("unexpected end of file ... ".to_string(), scanner.line))}
+                    '\0' => {return Err(scanner.syntax_error("unexpected end of file ... ".to_string()))}
                     _ => {
-                        return Err(crate::scanner::make_error_msg(format!("fields must be separated by commas (i.e., ',') .... found character '{}' ", scanner.peek()), scanner.line))
+                        return Err(scanner.syntax_error(format!("fields must be separated by commas (i.e., ',') .... found character '{}' ", scanner.peek())))
                     }
                 }
 
@@ -193,6 +255,12 @@ impl StructObject {
         /********************** */
         /*    VERIFY VALUES     */
         /********************** */
+        from_bytes = quote!(
+            #from_bytes
+
+            #[allow(unused_variables)]
+            let object_name_for_errors: Option<String> = #object_name_for_errors;
+        );
         for f in self.fields.iter() {
             // Skip State elements and index
             if let Field::State(_) = f {
@@ -225,6 +293,14 @@ impl StructObject {
         // Fill with optional values
         for f in self.fields.iter() {
             match f {
+                Field::Option(d) if d.interior => {
+                    let f_ident = d.ident.clone().unwrap();
+                    from_bytes = quote!(
+                        #from_bytes
+
+                        ret.#f_ident = std::cell::Cell::new(#f_ident);
+                    );
+                }
                 Field::Option(d) | Field::Vec(d) => {
                     let f_ident = d.ident.clone().unwrap();
                     // let f_ident = "car";
@@ -245,7 +321,7 @@ impl StructObject {
         );
         quote!(
             #[doc = #from_bytes_docstring]
-            pub fn from_bytes(line: usize, bytes: &[u8], model: &crate::model::SimpleModel)->Result<Self, String>{
+            pub fn from_bytes(line: usize, bytes: &[u8], model: &crate::model::SimpleModel)->Result<Self, crate::SimpleModelError>{
                 #from_bytes
 
                 Ok(ret)
@@ -253,6 +329,66 @@ impl StructObject {
         )
     }
 
+    pub fn gen_to_spl(&self) -> TokenStream2 {
+        let object_name = &self.ident;
+        let name_str = format!("{}", object_name);
+
+        // Write every field that has a value... State elements
+        // and index are not part of the input
+        let mut content = quote!(
+            let mut fields: Vec<String> = Vec::new();
+        );
+        for f in self.fields.iter() {
+            if let Field::State(_) = f {
+                continue;
+            }
+            let f_ident = f.data().ident.clone().unwrap();
+            let field_name = format!("{}", f_ident);
+            if field_name == "index" {
+                continue;
+            }
+
+            if let Field::Option(d) = f {
+                let writer = f.get_writer(&quote!(v));
+                let current = if d.interior {
+                    quote!(self.#f_ident.get())
+                } else {
+                    quote!(self.#f_ident)
+                };
+                content = quote!(
+                    #content
+                    if let Some(v) = &#current {
+                        fields.push(format!("    {}: {}", #field_name, #writer));
+                    }
+                );
+            } else {
+                let writer = f.get_writer(&quote!(self.#f_ident));
+                content = quote!(
+                    #content
+                    fields.push(format!("    {}: {}", #field_name, #writer));
+                );
+            }
+        }
+
+        let to_spl_docstring = format!(
+            " Writes the [`{}`] in the same format that is read by `from_bytes()`",
+            object_name
+        );
+        quote!(
+            /// Writes the fields of this object, between braces
+            /// (i.e., `{ ... }`). This is the part that is read by `from_bytes()`
+            pub fn to_spl_body(&self) -> String {
+                #content
+                format!("{{\n{}\n}}", fields.join(",\n"))
+            }
+
+            #[doc = #to_spl_docstring]
+            pub fn to_spl(&self) -> String {
+                format!("{} {}", #name_str, self.to_spl_body())
+            }
+        )
+    }
+
     pub fn gen_new(&self) -> TokenStream2 {
         let req_field_names = self.collect_required_fields();
         let new_docstring = format!(" Creates a new [`{}`]", self.ident);
@@ -270,6 +406,12 @@ impl StructObject {
                         #fname : std::cell::RefCell::new(None),
                     )
                 }
+                Field::Option(d) if d.interior => {
+                    content = quote!(
+                        #content
+                        #fname : std::cell::Cell::new(None),
+                    )
+                }
                 Field::Option(_) => {
                     content = quote!(
                         #content
@@ -358,12 +500,21 @@ impl StructObject {
     pub fn gen_state_getters_setters(&self) -> TokenStream2 {
         let mut gets: TokenStream2 = quote!();
         let mut sets: TokenStream2 = quote!();
+        let mut maps: TokenStream2 = quote!();
 
         for f in self.fields.iter() {
             // name of the field
             let f_ident = f.data().ident.clone().unwrap();
             match f {
                 Field::State(_d) => {
+                    /* CHANGE THE INDEX OF THE OBJECT */
+                    maps = quote!(
+                        #maps
+                        let mut i = self.#f_ident.borrow_mut();
+                        *i = i.and_then(f);
+                        drop(i);
+                    );
+
                     /* SET THE INDEX OF THE OBJECT */
                     // name of the 'set_index_' method
                     let set_ident = format!("set_{}_index", f_ident);
@@ -440,6 +591,40 @@ impl StructObject {
                         }
                     );
                 }
+                Field::Option(d) if d.interior => {
+                    let f_ident_str = format!("{}", f_ident);
+                    let ty = d.child.clone().unwrap().data().ty;
+
+                    let set_ident = format!("set_{}", f_ident);
+                    let set_ident = syn::Ident::new(&set_ident, f_ident.span());
+                    let sets_doc_string = format!(" Sets the `{}` field. This can be done after the object has been added to the model", f_ident);
+                    sets = quote!(
+                        #sets
+
+                        #[doc = #sets_doc_string]
+                        pub fn #set_ident(&self, v: #ty)->&Self{
+                            self.#f_ident.set(Some(v));
+                            self
+                        }
+                    );
+
+                    let gets_doc_string = format!(" Gets (a copy of) the `{}` field. Returns a `Result` because this field is optional and thus it might not be there.", f_ident);
+                    gets = quote!(
+                        #gets
+
+                        #[doc = #gets_doc_string]
+                        pub fn #f_ident(&self) -> Result<#ty, crate::SimpleModelError> {
+                            match self.#f_ident.get() {
+                                Some(v) => Ok(v),
+                                None => Err(crate::SimpleModelError::UnassignedField{
+                                    object_type: self.object_type().to_string(),
+                                    object_name: self.name.clone(),
+                                    field: #f_ident_str.to_string(),
+                                }),
+                            }
+                        }
+                    );
+                }
                 Field::Option(d) => {
                     let f_ident_str = format!("{}", f_ident);
                     // Type T inside the Option<T>
@@ -470,10 +655,14 @@ impl StructObject {
                         #gets
 
                         #[doc = #gets_doc_string]
-                        pub fn #f_ident(&self) -> Result<&#ty, String> {
+                        pub fn #f_ident(&self) -> Result<&#ty, crate::SimpleModelError> {
                             match &self.#f_ident {
                                 Some(v) => Ok(v),
-                                None => Err(format!("{} called '{}' has not been assigned any value for field '{}'", self.object_type(), self.name, #f_ident_str)),
+                                None => Err(crate::SimpleModelError::UnassignedField{
+                                    object_type: self.object_type().to_string(),
+                                    object_name: self.name.clone(),
+                                    field: #f_ident_str.to_string(),
+                                }),
                             }
                         }
                     );
@@ -482,6 +671,21 @@ impl StructObject {
             } // End of match
         } // end of fields.iter()
 
+        // Only objects with state elements can map them
+        if !maps.is_empty() {
+            sets = quote!(
+                #sets
+
+                /// Changes the indices of all the [`SimulationStateElement`] of this object
+                /// within the [`SimulationState`]. The function `f` receives the current index
+                /// of each element that has one, and returns its new index (or `None`, for
+                /// forgetting it).
+                pub fn map_state_indices(&self, f: &dyn Fn(usize) -> Option<usize>){
+                    #maps
+                }
+            );
+        }
+
         quote!(
             #gets
 
@@ -500,15 +704,23 @@ impl StructObject {
             docs
         );
 
+        let mut state_api_names: Vec<String> = Vec::new();
+        for field in self.fields.iter() {
+            if let Field::State(_) = field {
+                state_api_names.push(field.api_name());
+            }
+        }
+
         for field in self.fields.iter() {
             if let Field::State(_) = field {
                 let data = field.data();
-                // Getters, Setters (and therefore, docs) are only for Operational and Physical fields
-                // for now.
+                // Getters, Setters (and therefore, docs) are only for Operational,
+                // Personal and Physical fields for now.
                 let att_names: Vec<String> =
                     data.attributes.iter().map(|x| x.name.clone()).collect();
                 if !att_names.contains(&"physical".to_string())
                     && !att_names.contains(&"operational".to_string())
+                    && !att_names.contains(&"personal".to_string())
                 {
                     continue;
                 }
@@ -535,9 +747,46 @@ impl StructObject {
                     #field_setters
                     #set
                 );
+            } else {
+                // Other fields are read-only, unless they are hidden
+                // by a state field with the same name in the API
+                let api_fieldname = field.api_name();
+                if api_fieldname == "index" || state_api_names.contains(&api_fieldname) {
+                    continue;
+                }
+                if let Some(get) = field.api_static_getter(&object_name) {
+                    docs = format!("{}\n| `{}` | Yes | No |", docs, api_fieldname);
+                    field_getters = quote!(
+                        #field_getters
+                        #get
+                    );
+                }
             }
         }
 
+        for (method, receives_model) in self.api_methods.iter() {
+            let api_fieldname = method.to_string();
+            docs = format!("{}\n| `{}` | Yes | No |", docs, api_fieldname);
+            let get = if *receives_model {
+                quote!(
+                    let new_mod = std::rc::Rc::clone(model);
+                    engine.register_get(#api_fieldname, move |this: &mut std::rc::Rc<#object_name>| {
+                        this.#method(&new_mod)
+                    });
+                )
+            } else {
+                quote!(
+                    engine.register_get(#api_fieldname, |this: &mut std::rc::Rc<#object_name>| {
+                        this.#method()
+                    });
+                )
+            };
+            field_getters = quote!(
+                #field_getters
+                #get
+            );
+        }
+
         // return
         (field_getters, field_setters, docs)
     }
@@ -604,6 +853,7 @@ impl StructObject {
             )
         });
         let location = syn::Ident::new(location_str, proc_macro2::Span::call_site());
+        let count_fn_name = format!("n_{}", location_str);
 
         // register_access_from_model
         let not_found_err = format!("Could not find {} '{{}}'", object_name);
@@ -642,6 +892,16 @@ impl StructObject {
                 Ok(std::rc::Rc::clone(&new_mod.#location[index as usize]))
             });
 
+            // All of them
+            let new_mod = std::rc::Rc::clone(model);
+            engine.register_fn(#location_str, move || -> rhai::Array {
+                new_mod.#location.iter().map(|s| rhai::Dynamic::from(std::rc::Rc::clone(s))).collect()
+            });
+
+            // How many
+            let new_mod = std::rc::Rc::clone(model);
+            engine.register_fn(#count_fn_name, move || new_mod.#location.len() as rhai::INT);
+
         );
 
         self.get_api(access_from_model)
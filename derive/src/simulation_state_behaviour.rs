@@ -146,3 +146,83 @@ pub fn derive_enum_kind(
         // }
     ))
 }
+
+pub fn derive_enum_encoding(
+    ast: &syn::DeriveInput,
+    variants: &syn::punctuated::Punctuated<syn::Variant, syn::token::Comma>,
+) -> TokenStream2 {
+    let enum_name = &ast.ident;
+
+    let mut name_arms = quote!();
+    let mut fields_arms = quote!();
+    let mut from_arms = quote!();
+    let mut names = Vec::with_capacity(variants.len());
+    for v in variants.iter() {
+        let v_ident = v.ident.clone();
+        let v_name = format!("{}", v_ident);
+        let field_idents: Vec<syn::Ident> = (0..v.fields.len())
+            .map(|j| quote::format_ident!("f{}", j))
+            .collect();
+
+        name_arms = quote!(
+            #name_arms
+            Self::#v_ident{..} => #v_name,
+        );
+        fields_arms = quote!(
+            #fields_arms
+            Self::#v_ident( #(#field_idents),* ) => vec![ #(*#field_idents),* ],
+        );
+        from_arms = quote!(
+            #from_arms
+            (#v_name, [ #(#field_idents),* ]) => Some(Self::#v_ident( #(*#field_idents),* )),
+        );
+        names.push(v_name);
+    }
+
+    let variant_name_docstring = format!(
+        " Gets the name of the variant of this [`{}`] (e.g., to store it \
+         in a way that does not depend on the order of the variants)",
+        enum_name
+    );
+    let variant_names_docstring =
+        format!(" Gets the names of all the variants of [`{}`]", enum_name);
+    let variant_fields_docstring = format!(
+        " Gets the (index) fields of this [`{}`], in order",
+        enum_name
+    );
+    let from_variant_docstring = format!(
+        " Builds a [`{}`] from the name of its variant and its fields (i.e., the \
+         inverse of `variant_name()` and `variant_fields()`). Returns `None` if \
+         there is no such variant, or if it does not have that number of fields.",
+        enum_name
+    );
+
+    quote!(
+        #[doc = #variant_name_docstring]
+        pub fn variant_name(&self) -> &'static str {
+            match self {
+                #name_arms
+            }
+        }
+
+        #[doc = #variant_names_docstring]
+        pub fn variant_names() -> &'static [&'static str] {
+            &[ #(#names),* ]
+        }
+
+        #[doc = #variant_fields_docstring]
+        pub fn variant_fields(&self) -> Vec<usize> {
+            match self {
+                #fields_arms
+            }
+        }
+
+        #[doc = #from_variant_docstring]
+        pub fn from_variant(name: &str, fields: &[usize]) -> Option<Self> {
+            match (name, fields) {
+                #from_arms
+                _ => None,
+            }
+        }
+    )
+}
@@ -0,0 +1,456 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::{Boundary, Float, SimpleModel, SimpleModelError, Surface};
+use geometry3d::{Point3D, Polygon3D};
+use std::rc::Rc;
+
+/// The distance (in meters) under which two points are
+/// considered to be the same
+const DISTANCE_TOLERANCE: Float = 1e-3;
+
+/// Two surfaces of different spaces that overlap, but not
+/// completely. These cannot be given a `Boundary::Space`
+/// automatically, so they need to be split by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartialOverlap {
+    /// The name of one of the surfaces
+    pub surface_a: String,
+
+    /// The name of the other surface
+    pub surface_b: String,
+}
+
+/// A 2D point, resulting from projecting a [`Point3D`] into a plane
+type Point2D = (Float, Float);
+
+/// Projects the vertices of a polygon into the coordinate plane
+/// that is most aligned with its `normal`
+fn project(polygon: &Polygon3D, normal: (Float, Float, Float)) -> Vec<Point2D> {
+    let (nx, ny, nz) = (normal.0.abs(), normal.1.abs(), normal.2.abs());
+    polygon
+        .outer()
+        .vertices()
+        .iter()
+        .map(|p| {
+            if nx >= ny && nx >= nz {
+                (p.y, p.z)
+            } else if ny >= nz {
+                (p.x, p.z)
+            } else {
+                (p.x, p.y)
+            }
+        })
+        .collect()
+}
+
+/// The cross product of `b - a` and `c - a`
+fn orientation(a: Point2D, b: Point2D, c: Point2D) -> Float {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Checks whether the segments `p1-p2` and `q1-q2` cross each other
+/// (i.e., touching at a vertex or overlapping collinearly does not count)
+fn segments_cross(p1: Point2D, p2: Point2D, q1: Point2D, q2: Point2D) -> bool {
+    let tol = DISTANCE_TOLERANCE * DISTANCE_TOLERANCE;
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+    ((d1 > tol && d2 < -tol) || (d1 < -tol && d2 > tol))
+        && ((d3 > tol && d4 < -tol) || (d3 < -tol && d4 > tol))
+}
+
+/// Checks whether `p` is strictly inside of `polygon` (i.e., points
+/// on its edges are not inside)
+fn strictly_inside(p: Point2D, polygon: &[Point2D]) -> bool {
+    let n = polygon.len();
+    // Points on the edges are not inside
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        let length = ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+        let within_box = p.0 >= a.0.min(b.0) - DISTANCE_TOLERANCE
+            && p.0 <= a.0.max(b.0) + DISTANCE_TOLERANCE
+            && p.1 >= a.1.min(b.1) - DISTANCE_TOLERANCE
+            && p.1 <= a.1.max(b.1) + DISTANCE_TOLERANCE;
+        if within_box && orientation(a, b, p).abs() <= DISTANCE_TOLERANCE * length {
+            return false;
+        }
+    }
+
+    // Ray casting
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        if (a.1 > p.1) != (b.1 > p.1) && p.0 < (b.0 - a.0) * (p.1 - a.1) / (b.1 - a.1) + a.0 {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+/// Checks whether two polygons have the same vertices (in any order
+/// and with any orientation)
+fn same_vertices(a: &Polygon3D, b: &Polygon3D) -> bool {
+    let (a, b) = (a.outer().vertices(), b.outer().vertices());
+    let close = |p: &Point3D, q: &Point3D| (*p - *q).length() <= DISTANCE_TOLERANCE;
+    a.len() == b.len()
+        && a.iter().all(|p| b.iter().any(|q| close(p, q)))
+        && b.iter().all(|p| a.iter().any(|q| close(p, q)))
+}
+
+/// Checks whether two polygons that face each other (i.e., they are
+/// coplanar and have opposite normals) overlap
+fn overlap(a: &Polygon3D, b: &Polygon3D) -> bool {
+    let (na, nb) = (a.normal(), b.normal());
+    // Facing each other
+    if na * nb > -1.0 + 1e-3 {
+        return false;
+    }
+    // In the same plane
+    let a0 = a.outer().vertices()[0];
+    if b.outer()
+        .vertices()
+        .iter()
+        .any(|p| ((*p - a0) * na).abs() > DISTANCE_TOLERANCE)
+    {
+        return false;
+    }
+
+    let normal = (na.x, na.y, na.z);
+    let (a, b) = (project(a, normal), project(b, normal));
+    let centroid = |poly: &[Point2D]| {
+        let n = poly.len() as Float;
+        let (x, y) = poly
+            .iter()
+            .fold((0., 0.), |acc, p| (acc.0 + p.0, acc.1 + p.1));
+        (x / n, y / n)
+    };
+
+    let crossing = (0..a.len()).any(|i| {
+        (0..b.len()).any(|j| segments_cross(a[i], a[(i + 1) % a.len()], b[j], b[(j + 1) % b.len()]))
+    });
+    crossing
+        || a.iter().any(|p| strictly_inside(*p, &b))
+        || b.iter().any(|p| strictly_inside(*p, &a))
+        || strictly_inside(centroid(&a), &b)
+        || strictly_inside(centroid(&b), &a)
+}
+
+/// The boundaries that [`SimpleModel::detect_adjacency`] found for a
+/// [`Surface`]
+#[derive(Clone)]
+pub struct SurfaceBoundaries {
+    /// The index of the surface
+    pub surface: usize,
+
+    /// The boundary in front of the surface (i.e., the `Space` of another
+    /// surface with the same vertices, or `Boundary::Ground`), or `None`
+    /// if it is outside
+    pub front: Option<Boundary>,
+
+    /// The boundary at the back of the surface (i.e., its own `Space`)
+    pub back: Boundary,
+}
+
+/// The result of [`SimpleModel::detect_adjacency`]
+#[derive(Clone)]
+pub struct Adjacency {
+    /// The boundaries of each of the surfaces whose `Space` is known
+    pub boundaries: Vec<SurfaceBoundaries>,
+
+    /// The pairs of surfaces of different spaces that overlap only
+    /// partly, so that they can be split.
+    pub partial_overlaps: Vec<PartialOverlap>,
+}
+
+impl Adjacency {
+    /// Sets the boundaries found to the `surfaces` (i.e., the same
+    /// ones given to [`SimpleModel::detect_adjacency`]), before
+    /// they are added to a model.
+    ///
+    /// Returns an error—without modifying any surface—if any of the
+    /// boundaries refers to a surface that does not exist.
+    pub fn apply(&self, surfaces: &mut [Surface]) -> Result<(), SimpleModelError> {
+        if let Some(b) = self.boundaries.iter().find(|b| b.surface >= surfaces.len()) {
+            return Err(SimpleModelError::IndexOutOfBounds {
+                object_type: "Surface".to_string(),
+                index: b.surface,
+                len: surfaces.len(),
+            });
+        }
+        for b in self.boundaries.iter() {
+            let surface = &mut surfaces[b.surface];
+            surface.set_back_boundary(b.back.clone());
+            if let Some(front) = &b.front {
+                surface.set_front_boundary(front.clone());
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SimpleModel {
+    /// Finds the boundaries of the [`Surface`]s of an imported geometry,
+    /// in which each `Space` is enclosed by its own surfaces.
+    ///
+    /// `surface_spaces` contains the `(surface_index, space_index)` of the
+    /// `surfaces` whose `Space`—in this model—is known. As in most imported
+    /// geometries, these surfaces are assumed to face away from their space,
+    /// so their back boundary is that space. Their front boundary is the `Space`
+    /// of another surface with the same vertices (if found); `Boundary::Ground`
+    /// if the surface is not above the `ground_height`; or none (i.e.,
+    /// outside) otherwise. Surfaces that are not listed are not assigned
+    /// any boundary.
+    ///
+    /// The `surfaces` are not modified: the boundaries found can be set
+    /// (see [`Adjacency::apply`]) before adding them to the model, which
+    /// is what [`SimpleModel::add_surfaces_with_adjacency`] does.
+    pub fn detect_adjacency(
+        &self,
+        surfaces: &[Surface],
+        surface_spaces: &[(usize, usize)],
+        ground_height: Float,
+    ) -> Result<Adjacency, SimpleModelError> {
+        for (surface, space) in surface_spaces {
+            if *surface >= surfaces.len() {
+                return Err(SimpleModelError::IndexOutOfBounds {
+                    object_type: "Surface".to_string(),
+                    index: *surface,
+                    len: surfaces.len(),
+                });
+            }
+            if *space >= self.spaces.len() {
+                return Err(SimpleModelError::IndexOutOfBounds {
+                    object_type: "Space".to_string(),
+                    index: *space,
+                    len: self.spaces.len(),
+                });
+            }
+        }
+
+        let mut partial_overlaps = Vec::new();
+
+        // Find the other side of each surface
+        let mut front: Vec<Option<Boundary>> = vec![None; surface_spaces.len()];
+        for (i, (surface_a, space_a)) in surface_spaces.iter().enumerate() {
+            for (j, (surface_b, space_b)) in surface_spaces.iter().enumerate().skip(i + 1) {
+                if space_a == space_b {
+                    continue;
+                }
+                let a = &surfaces[*surface_a];
+                let b = &surfaces[*surface_b];
+                if same_vertices(&a.vertices, &b.vertices) {
+                    front[i] = Some(Boundary::Space(Rc::clone(&self.spaces[*space_b])));
+                    front[j] = Some(Boundary::Space(Rc::clone(&self.spaces[*space_a])));
+                } else if overlap(&a.vertices, &b.vertices) {
+                    partial_overlaps.push(PartialOverlap {
+                        surface_a: a.name.clone(),
+                        surface_b: b.name.clone(),
+                    })
+                }
+            }
+        }
+
+        // Surfaces in contact with the ground
+        for (i, (surface, _)) in surface_spaces.iter().enumerate() {
+            let max_height = surfaces[*surface]
+                .vertices
+                .outer()
+                .vertices()
+                .iter()
+                .fold(Float::MIN, |h, p| h.max(p.z));
+            if front[i].is_none() && max_height <= ground_height + DISTANCE_TOLERANCE {
+                front[i] = Some(Boundary::Ground);
+            }
+        }
+
+        let boundaries = surface_spaces
+            .iter()
+            .zip(front)
+            .map(|((surface, space), front)| SurfaceBoundaries {
+                surface: *surface,
+                front,
+                back: Boundary::Space(Rc::clone(&self.spaces[*space])),
+            })
+            .collect();
+
+        Ok(Adjacency {
+            boundaries,
+            partial_overlaps,
+        })
+    }
+
+    /// Adds the [`Surface`]s of an imported geometry to the model, with the
+    /// boundaries found by [`SimpleModel::detect_adjacency`] (see its
+    /// documentation for the meaning of `surface_spaces` and `ground_height`).
+    ///
+    /// Returns the pairs of surfaces that overlap only partly, which are
+    /// added without a `Boundary::Space` and need to be split by the user.
+    /// If any index is out of bounds, nothing is added.
+    pub fn add_surfaces_with_adjacency(
+        &mut self,
+        mut surfaces: Vec<Surface>,
+        surface_spaces: &[(usize, usize)],
+        ground_height: Float,
+    ) -> Result<Vec<PartialOverlap>, SimpleModelError> {
+        let adjacency = self.detect_adjacency(&surfaces, surface_spaces, ground_height)?;
+        adjacency.apply(&mut surfaces)?;
+        for surface in surfaces {
+            self.add_surface(surface);
+        }
+        Ok(adjacency.partial_overlaps)
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_strictly_inside() {
+        let square = vec![(0., 0.), (1., 0.), (1., 1.), (0., 1.)];
+        assert!(strictly_inside((0.5, 0.5), &square));
+        assert!(!strictly_inside((1.5, 0.5), &square));
+        assert!(!strictly_inside((1., 0.5), &square));
+        assert!(!strictly_inside((0., 0.), &square));
+    }
+
+    #[test]
+    fn test_detect_adjacency() {
+        // Two rooms, side by side, with their own walls
+        let src = b"
+Substance::Normal { name: \"the substance\" }
+
+Material {
+    name: \"the material\",
+    substance: \"the substance\",
+    thickness: 0.1
+}
+
+Construction {
+    name: \"the construction\",
+    materials: [\"the material\"]
+}
+
+Space { name: \"left\" }
+Space { name: \"right\" }
+
+Surface {
+    name: \"left wall\",
+    construction: \"the construction\",
+    vertices: [1, 0, 0, 1, 1, 0, 1, 1, 1, 1, 0, 1]
+}
+
+Surface {
+    name: \"right wall\",
+    construction: \"the construction\",
+    vertices: [1, 0, 0, 1, 0, 1, 1, 1, 1, 1, 1, 0]
+}
+
+Surface {
+    name: \"left floor\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 0, 0]
+}
+
+Surface {
+    name: \"right wall, too tall\",
+    construction: \"the construction\",
+    vertices: [1, 0.2, 0.2, 1, 0.2, 2, 1, 0.8, 2, 1, 0.8, 0.2]
+}
+
+Surface {
+    name: \"left exterior wall\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 0]
+}
+";
+        let (mut model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        // Import the surfaces again, as if they came from another tool
+        let surfaces: Vec<Surface> = std::mem::take(&mut model.surfaces)
+            .iter()
+            .map(|s| (**s).clone())
+            .collect();
+
+        // Indices are checked
+        assert!(model
+            .detect_adjacency(&surfaces, &[(0, 0), (5, 0)], 0.0)
+            .is_err());
+        assert!(model.detect_adjacency(&surfaces, &[(0, 2)], 0.0).is_err());
+        let adjacency = model.detect_adjacency(&surfaces, &[(4, 0)], 0.0).unwrap();
+        let mut too_few = surfaces[0..2].to_vec();
+        assert!(adjacency.apply(&mut too_few).is_err());
+        assert!(too_few[1].back_boundary().is_err());
+        assert!(model
+            .add_surfaces_with_adjacency(surfaces.clone(), &[(0, 0), (5, 0)], 0.0)
+            .is_err());
+        assert!(model.surfaces.is_empty());
+
+        // The floor is not listed
+        let partial_overlaps = model
+            .add_surfaces_with_adjacency(surfaces.clone(), &[(0, 0), (1, 1), (3, 1), (4, 0)], 0.0)
+            .unwrap();
+        assert_eq!(
+            partial_overlaps,
+            vec![PartialOverlap {
+                surface_a: "left wall".to_string(),
+                surface_b: "right wall, too tall".to_string()
+            }]
+        );
+        assert_eq!(model.surfaces.len(), 5);
+
+        let front_space = |i: usize| match model.surfaces[i].front_boundary() {
+            Ok(Boundary::Space(s)) => Some(s.name.clone()),
+            _ => None,
+        };
+        let back_space = |i: usize| match model.surfaces[i].back_boundary() {
+            Ok(Boundary::Space(s)) => Some(s.name.clone()),
+            _ => None,
+        };
+
+        // Left and right walls see each other
+        assert_eq!(front_space(0), Some("right".to_string()));
+        assert_eq!(back_space(0), Some("left".to_string()));
+        assert_eq!(front_space(1), Some("left".to_string()));
+        assert_eq!(back_space(1), Some("right".to_string()));
+
+        // The wall that overlaps only partly, and the one with
+        // nothing in front of it, are exterior
+        assert!(model.surfaces[3].front_boundary().is_err());
+        assert_eq!(back_space(3), Some("right".to_string()));
+        assert!(model.surfaces[4].front_boundary().is_err());
+        assert_eq!(back_space(4), Some("left".to_string()));
+
+        // The floor touches the ground, but it was not listed
+        assert!(model.surfaces[2].front_boundary().is_err());
+        assert!(model.surfaces[2].back_boundary().is_err());
+        let adjacency = model.detect_adjacency(&surfaces, &[(2, 0)], 0.0).unwrap();
+        assert!(matches!(
+            adjacency.boundaries[0].front,
+            Some(Boundary::Ground)
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::boundary::Boundary;
+use crate::fenestration::{Fenestration, FenestrationPositions, FenestrationType};
+use crate::model::SimpleModel;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::{Float, SimpleModelError};
+use derive::{ObjectAPI, ObjectIO};
+
+use std::rc::Rc;
+
+/// An exchange of air between two [`Space`]s, either directly (e.g., through
+/// a large opening) or through an operable door (i.e., a [`Fenestration`]
+/// of type `FenestrationType::Door`).
+///
+/// All the `AirExchange` objects connecting the same pair of spaces in the
+/// same direction share their state element, which holds the total volume of
+/// air moving from `from_space` into `to_space`.
+///
+/// ### Example
+///
+/// ```rs
+/// AirExchange {
+///     name: "Kitchen door",
+///     from_space: "Kitchen",
+///     to_space: "Living room",
+///     fenestration: "Kitchen door"
+/// }
+/// ```
+#[derive(ObjectIO, ObjectAPI, Clone)]
+pub struct AirExchange {
+    /// The name of the AirExchange
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The `Space` from which the air comes
+    pub from_space: Rc<Space>,
+
+    /// The `Space` into which the air goes
+    pub to_space: Rc<Space>,
+
+    /// The door through which the air moves. If none is
+    /// given, the spaces are connected directly.
+    fenestration: Option<Rc<Fenestration>>,
+
+    /// The maximum volume of air that can be exchanged, in m3/s
+    max_flow: Option<Float>,
+
+    /// The index of the volume of air moving from
+    /// `from_space` into `to_space`, in the State array
+    #[physical]
+    volume: StateElementField,
+}
+
+impl AirExchange {
+    /// Checks that the [`AirExchange`] connects two different [`Space`]s and
+    /// that, if it has a [`Fenestration`], this is an operable door between them
+    pub fn check(&self) -> Result<(), SimpleModelError> {
+        let err = |message: &str| {
+            Err(SimpleModelError::InvalidProperty {
+                object_type: "AirExchange".to_string(),
+                object_name: self.name.clone(),
+                message: message.to_string(),
+            })
+        };
+        if Rc::ptr_eq(&self.from_space, &self.to_space) {
+            return err("it connects a space with itself");
+        }
+        if let Ok(door) = self.fenestration() {
+            if door.fenestration_type != FenestrationType::Door {
+                return err("its fenestration is not a Door");
+            }
+            if door.operation_type == FenestrationPositions::FixedClosed {
+                return err("its fenestration cannot be opened");
+            }
+            let is_space = |b: Result<&Boundary, SimpleModelError>, s: &Rc<Space>| matches!(b, Ok(Boundary::Space(b)) if Rc::ptr_eq(b, s));
+            let (front, back) = (door.front_boundary(), door.back_boundary());
+            let connects = (is_space(front.clone(), &self.from_space)
+                && is_space(back.clone(), &self.to_space))
+                || (is_space(front, &self.to_space) && is_space(back, &self.from_space));
+            if !connects {
+                return err("its fenestration is not between 'from_space' and 'to_space'");
+            }
+        }
+        Ok(())
+    }
+
+    /// Pushes the physical states of the [`AirExchange`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// If the header already has the volume of air moving between these
+    /// [`Space`]s in this direction, that element is used instead.
+    pub(crate) fn push_physical_states(&self, state: &mut SimulationStateHeader) {
+        let from = *self
+            .from_space
+            .index()
+            .expect("Space of AirExchange has not been added to the model");
+        let to = *self
+            .to_space
+            .index()
+            .expect("Space of AirExchange has not been added to the model");
+        let state_index = state.air_exchange_index(from, to).unwrap_or_else(|| {
+            state.push(SimulationStateElement::SpaceAirExchangeVolume(from, to), 0.)
+        });
+        self.set_volume_index(state_index);
+    }
+}
+
+impl SimulationStateHeader {
+    /// Finds the position of the volume of air moving from one
+    /// [`Space`] into another (given their indexes) in the State. The
+    /// volume moving in the opposite direction is a different element.
+    pub fn air_exchange_index(&self, from_space: usize, to_space: usize) -> Option<usize> {
+        self.find(&SimulationStateElement::SpaceAirExchangeVolume(
+            from_space, to_space,
+        ))
+    }
+}
+
+impl SimpleModel {
+    /// Adds an [`AirExchange`] to the [`SimpleModel`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of its [`Space`]s has not been added to the
+    /// model (i.e., it has no index)
+    pub fn add_air_exchange(
+        &mut self,
+        mut add: AirExchange,
+        state: &mut SimulationStateHeader,
+    ) -> Rc<AirExchange> {
+        // Check the index of this object
+        let obj_index = self.air_exchanges.len();
+        add.set_index(obj_index);
+
+        // Push the state (unless this pair of spaces has it already),
+        // and map into the object
+        add.push_physical_states(state);
+
+        // Add to model, and return a reference
+        let add = Rc::new(add);
+        self.air_exchanges.push(Rc::clone(&add));
+        add
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_air_exchange() {
+        let src = b"
+Space { name: \"kitchen\" }
+Space { name: \"living room\" }
+
+Substance::Normal { name: \"the substance\" }
+Material { name: \"the material\", substance: \"the substance\", thickness: 0.1 }
+Construction { name: \"the construction\", materials: [\"the material\"] }
+
+Fenestration {
+    name: \"the door\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::Continuous(),
+    fenestration_type: FenestrationType::Door(),
+    front_boundary: Boundary::Space(\"kitchen\"),
+    back_boundary: Boundary::Space(\"living room\"),
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 2, 0, 0, 2]
+}
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::Continuous(),
+    fenestration_type: FenestrationType::Window(),
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1]
+}
+
+AirExchange {
+    name: \"through the door\",
+    from_space: \"living room\",
+    to_space: \"kitchen\",
+    fenestration: \"the door\"
+}
+
+AirExchange {
+    name: \"through the window\",
+    from_space: \"living room\",
+    to_space: \"kitchen\",
+    fenestration: \"the window\"
+}
+
+AirExchange {
+    name: \"the other way\",
+    from_space: \"kitchen\",
+    to_space: \"living room\"
+}
+";
+        let (model, mut header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.air_exchanges.len(), 3);
+        assert!(model.air_exchanges[0].check().is_ok());
+        assert!(model.air_exchanges[1].check().is_err());
+        assert!(model.air_exchanges[2].check().is_ok());
+
+        // Lookup by space pair
+        let living_to_kitchen = header.air_exchange_index(1, 0).unwrap();
+        let kitchen_to_living = header.air_exchange_index(0, 1).unwrap();
+        assert_ne!(living_to_kitchen, kitchen_to_living);
+        assert_eq!(
+            header.elements[living_to_kitchen],
+            SimulationStateElement::SpaceAirExchangeVolume(1, 0)
+        );
+        assert!(header.air_exchange_index(1, 1).is_none());
+
+        // Same pair of spaces, same state element
+        let mut state = header.take_values().unwrap();
+        assert_eq!(
+            model.air_exchanges[0].volume_index(),
+            Some(living_to_kitchen)
+        );
+        assert_eq!(
+            model.air_exchanges[1].volume_index(),
+            Some(living_to_kitchen)
+        );
+        model.air_exchanges[2].set_volume(&mut state, 0.5);
+        assert_eq!(state[kitchen_to_living], 0.5);
+    }
+}
@@ -20,6 +20,8 @@ SOFTWARE.
 
 use crate::material::Material;
 use crate::model::SimpleModel;
+use crate::substance::Substance;
+use crate::{Float, SimpleModelError};
 use derive::ObjectIO;
 use std::rc::Rc;
 
@@ -42,7 +44,107 @@ pub struct Construction {
     // back finishing
 }
 
+/// The interior surface film coefficient (in W/m2.K) for horizontal
+/// heat flow, from ISO 6946 (i.e., a resistance of 0.13 m2.K/W)
+pub const STANDARD_INTERIOR_FILM_COEFFICIENT: Float = 1. / 0.13;
 
+/// The exterior surface film coefficient (in W/m2.K), from
+/// ISO 6946 (i.e., a resistance of 0.04 m2.K/W)
+pub const STANDARD_EXTERIOR_FILM_COEFFICIENT: Float = 1. / 0.04;
+
+/// The mean temperature (in K) at which the properties of
+/// the gas layers are calculated
+const GAS_MEAN_TEMPERATURE: Float = 283.15;
+
+/// The emissivity assumed for the surfaces facing a gas layer, when
+/// they do not have a `thermal_absorbtance` (i.e., uncoated glass)
+const DEFAULT_EMISSIVITY: Float = 0.84;
+
+/// The Stefan-Boltzmann constant, in W/m2.K4
+const SIGMA: Float = 5.670374419e-8;
+
+impl Construction {
+    /// The total thickness of the [`Construction`], in m
+    pub fn thickness(&self) -> Float {
+        self.materials.iter().map(|m| m.thickness).sum()
+    }
+
+    /// The emissivity of the layer in position `i`, as seen from a gas layer
+    fn emissivity(&self, i: usize) -> Float {
+        match self.materials.get(i).map(|m| &m.substance) {
+            Some(Substance::Normal(s)) => s
+                .thermal_absorbtance()
+                .copied()
+                .unwrap_or(DEFAULT_EMISSIVITY),
+            _ => DEFAULT_EMISSIVITY,
+        }
+    }
+
+    /// The thermal resistance of the layers of the [`Construction`]
+    /// (i.e., surface to surface, without film coefficients), in m2.K/W.
+    ///
+    /// Gas layers are assumed to be narrow enough for convection to be
+    /// negligible, so they transfer heat by conduction—using the
+    /// [`Gas::thermal_conductivity`](crate::substance::gas::Gas::thermal_conductivity)
+    /// of their substance, which also handles custom gases and mixtures—and
+    /// by radiation between the layers at each side.
+    pub fn r_value(&self) -> Result<Float, SimpleModelError> {
+        let mut r = 0.0;
+        for (i, material) in self.materials.iter().enumerate() {
+            r += match &material.substance {
+                Substance::Normal(s) => material.thickness / s.thermal_conductivity()?,
+                Substance::Gas(g) => {
+                    let conduction =
+                        g.thermal_conductivity(GAS_MEAN_TEMPERATURE)? / material.thickness;
+                    let e1 = if i > 0 {
+                        self.emissivity(i - 1)
+                    } else {
+                        DEFAULT_EMISSIVITY
+                    };
+                    let e2 = self.emissivity(i + 1);
+                    let radiation =
+                        4. * SIGMA * GAS_MEAN_TEMPERATURE.powi(3) / (1. / e1 + 1. / e2 - 1.);
+                    1. / (conduction + radiation)
+                }
+            };
+        }
+        Ok(r)
+    }
+
+    /// The thermal transmittance of the [`Construction`] (in W/m2.K),
+    /// including the given interior and exterior film coefficients (in W/m2.K)
+    pub fn u_value(
+        &self,
+        interior_film_coefficient: Float,
+        exterior_film_coefficient: Float,
+    ) -> Result<Float, SimpleModelError> {
+        let r = 1. / interior_film_coefficient + self.r_value()? + 1. / exterior_film_coefficient;
+        Ok(1. / r)
+    }
+
+    /// The thermal transmittance of the [`Construction`] (in W/m2.K), using
+    /// the [`STANDARD_INTERIOR_FILM_COEFFICIENT`] and the
+    /// [`STANDARD_EXTERIOR_FILM_COEFFICIENT`]
+    pub fn standard_u_value(&self) -> Result<Float, SimpleModelError> {
+        self.u_value(
+            STANDARD_INTERIOR_FILM_COEFFICIENT,
+            STANDARD_EXTERIOR_FILM_COEFFICIENT,
+        )
+    }
+
+    /// The areal heat capacity of the [`Construction`] (i.e., the
+    /// sum of the density times the specific heat capacity times the
+    /// thickness of each layer), in J/m2.K. Gas layers are ignored.
+    pub fn areal_heat_capacity(&self) -> Result<Float, SimpleModelError> {
+        let mut c = 0.0;
+        for material in self.materials.iter() {
+            if let Substance::Normal(s) = &material.substance {
+                c += s.density()? * s.specific_heat_capacity()? * material.thickness;
+            }
+        }
+        Ok(c)
+    }
+}
 
 impl SimpleModel {
     /// Adds a [`Construction`] to the [`SimpleModel`]
@@ -133,4 +235,109 @@ mod testing {
         let construction = Construction::from_bytes(1, bytes, &mut building).unwrap();
         assert!(Rc::ptr_eq(&mat, &construction.materials[0]));
     }
+
+    #[test]
+    fn test_thermal_properties() {
+        let src = b"
+Substance::Normal {
+    name: \"concrete\",
+    thermal_conductivity: 1.6,
+    specific_heat_capacity: 900,
+    density: 2300
+}
+
+Substance::Normal {
+    name: \"glass\",
+    thermal_conductivity: 1,
+    specific_heat_capacity: 840,
+    density: 2500,
+    thermal_absorbtance: 0.84
+}
+
+Substance::Gas {
+    name: \"argon\",
+    kind: StandardGas::Argon()
+}
+
+Substance::Gas {
+    name: \"custom argon\",
+    thermal_conductivity_coefficients: [0.002285, 0.00005149, 0],
+    dynamic_viscosity_coefficients: [0.000003379, 0.00000006451, 0],
+    specific_heat_capacity_coefficients: [521.9285, 0, 0],
+    molecular_mass: 39.948
+}
+
+Material {
+    name: \"concrete layer\",
+    substance: \"concrete\",
+    thickness: 0.2
+}
+
+Material {
+    name: \"glass layer\",
+    substance: \"glass\",
+    thickness: 0.004
+}
+
+Material {
+    name: \"argon layer\",
+    substance: \"argon\",
+    thickness: 0.012
+}
+
+Material {
+    name: \"custom argon layer\",
+    substance: \"custom argon\",
+    thickness: 0.012
+}
+
+Construction {
+    name: \"wall\",
+    materials: [\"concrete layer\"]
+}
+
+Construction {
+    name: \"window\",
+    materials: [\"glass layer\", \"argon layer\", \"glass layer\"]
+}
+
+Construction {
+    name: \"custom window\",
+    materials: [\"glass layer\", \"custom argon layer\", \"glass layer\"]
+}
+";
+        let (model, _header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+
+        let wall = &model.constructions[0];
+        assert!((wall.thickness() - 0.2).abs() < 1e-6);
+        assert!((wall.r_value().unwrap() - 0.125).abs() < 1e-6);
+        let u = 1. / (0.13 + 0.125 + 0.04);
+        assert!((wall.standard_u_value().unwrap() - u).abs() < 1e-4);
+        assert!((wall.u_value(4., 20.).unwrap() - 1. / (0.25 + 0.125 + 0.05)).abs() < 1e-4);
+        assert!((wall.areal_heat_capacity().unwrap() - 2300. * 900. * 0.2).abs() < 1e-3);
+
+        // A double glazing with argon: about 2.6 W/m2.K
+        let window = &model.constructions[1];
+        assert!((window.thickness() - 0.02).abs() < 1e-6);
+        let u = window.standard_u_value().unwrap();
+        assert!(u > 2.4 && u < 2.9, "found {}", u);
+        assert!((window.areal_heat_capacity().unwrap() - 2. * 2500. * 840. * 0.004).abs() < 1e-3);
+
+        // Custom gases are handled through their own properties
+        let custom_window = &model.constructions[2];
+        assert!((custom_window.standard_u_value().unwrap() - u).abs() < 1e-9);
+
+        // Missing properties are reported
+        let mut model = SimpleModel::new("the model".to_string());
+        let sub = model.add_substance(Normal::new("the_sub".to_string()).wrap());
+        let mut c = Construction::new("c".to_string());
+        c.materials
+            .push(Rc::new(Material::new("m".to_string(), sub, 0.1)));
+        assert!(matches!(
+            c.r_value(),
+            Err(SimpleModelError::UnassignedField { .. })
+        ));
+    }
 }
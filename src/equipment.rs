@@ -0,0 +1,214 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::{Float, SimpleModelError};
+use derive::{ObjectAPI, ObjectIO};
+
+use std::rc::Rc;
+
+/// The kind of energy consumed by an [`Equipment`]
+#[derive(Copy, Clone, Eq, PartialEq, ObjectIO)]
+pub enum EquipmentFuel {
+    /// Electric equipment (e.g., computers, appliances)
+    Electric,
+
+    /// Gas-fired equipment (e.g., a stove)
+    Gas,
+
+    /// Any other kind of equipment
+    Other,
+}
+
+/// An Equipment (e.g., a computer or a stove), which adds
+/// heat to a [`Space`].
+///
+/// The heat it releases is split into radiant, latent and
+/// convective heat. The convective fraction is whatever is
+/// not radiant or latent.
+///
+/// ### Example
+///
+/// ```rs
+/// Equipment {
+///     name: "Kitchen stove",
+///     fuel: EquipmentFuel::Gas,
+///     max_power: 2000,
+///     target_space: "Kitchen",
+///     radiant_fraction: 0.3,
+///     latent_fraction: 0.2
+/// }
+/// ```
+#[derive(ObjectIO, ObjectAPI, Clone)]
+pub struct Equipment {
+    /// The name of the Equipment
+    name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The kind of energy consumed by the Equipment
+    fuel: EquipmentFuel,
+
+    /// The maximum power consumption
+    max_power: Option<Float>,
+
+    /// The space in which the Equipment is located, and
+    /// into which its heat is released
+    target_space: Option<Rc<Space>>,
+
+    /// The fraction of the heat that is released as
+    /// radiation (from 0 to 1). Defaults to 0.
+    radiant_fraction: Option<Float>,
+
+    /// The fraction of the heat that is released as
+    /// latent heat (i.e., as moisture; from 0 to 1). Defaults to 0.
+    latent_fraction: Option<Float>,
+
+    /// The fraction of the `max_power` that is available
+    /// over time (from 0 to 1)
+    availability: Option<Rc<Schedule>>,
+
+    /// The index of the state of the Equipment
+    /// in the State array
+    #[operational]
+    power_consumption: StateElementField,
+}
+
+impl Equipment {
+    /// The kind of energy consumed by the [`Equipment`]
+    pub fn fuel(&self) -> EquipmentFuel {
+        self.fuel
+    }
+
+    /// Splits a certain amount of heat released by the [`Equipment`]
+    /// into its radiant, convective and latent parts (in that order)
+    pub fn split_heat_gain(&self, heat: Float) -> Result<(Float, Float, Float), SimpleModelError> {
+        let radiant = self.radiant_fraction().copied().unwrap_or(0.0);
+        let latent = self.latent_fraction().copied().unwrap_or(0.0);
+        if radiant < 0.0 || latent < 0.0 || radiant + latent > 1.0 {
+            return Err(SimpleModelError::InvalidProperty {
+                object_type: "Equipment".to_string(),
+                object_name: self.name.clone(),
+                message: format!(
+                    "radiant_fraction ({}) and latent_fraction ({}) must be positive and add up to 1 or less",
+                    radiant, latent
+                ),
+            });
+        }
+        let convective = 1.0 - radiant - latent;
+        Ok((heat * radiant, heat * convective, heat * latent))
+    }
+
+    /// Pushes the operational states of the [`Equipment`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Equipment has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::EquipmentPowerConsumption(obj_index),
+            0.,
+        );
+        self.set_power_consumption_index(state_index);
+    }
+}
+
+impl SimpleModel {
+    /// Adds an [`Equipment`] to the [`SimpleModel`]
+    pub fn add_equipment(
+        &mut self,
+        mut add: Equipment,
+        state: &mut SimulationStateHeader,
+    ) -> Rc<Equipment> {
+        // Check the index of this object
+        let obj_index = self.equipment.len();
+        add.set_index(obj_index);
+
+        // Push the state, and map into the object
+        add.push_operational_states(state);
+
+        // Add to model, and return a reference
+        let add = Rc::new(add);
+        self.equipment.push(Rc::clone(&add));
+        add
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_equipment() {
+        let src = b"
+Space { name: \"kitchen\" }
+
+Equipment {
+    name: \"the stove\",
+    fuel: EquipmentFuel::Gas(),
+    max_power: 2000,
+    target_space: \"kitchen\",
+    radiant_fraction: 0.3,
+    latent_fraction: 0.2
+}
+
+Equipment {
+    name: \"the fridge\",
+    fuel: EquipmentFuel::Electric(),
+    radiant_fraction: 0.8,
+    latent_fraction: 0.3
+}
+";
+        let (model, mut header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.equipment.len(), 2);
+
+        let stove = &model.equipment[0];
+        assert!(stove.fuel() == EquipmentFuel::Gas);
+        assert_eq!(*stove.max_power().unwrap(), 2000.);
+        assert!(Rc::ptr_eq(stove.target_space().unwrap(), &model.spaces[0]));
+        let (radiant, convective, latent) = stove.split_heat_gain(100.).unwrap();
+        assert!((radiant - 30.).abs() < 1e-6);
+        assert!((convective - 50.).abs() < 1e-6);
+        assert!((latent - 20.).abs() < 1e-6);
+
+        // Fractions add up to more than 1
+        assert!(model.equipment[1].split_heat_gain(100.).is_err());
+
+        // Power consumption
+        let i = stove.power_consumption_index().unwrap();
+        assert_eq!(
+            header.elements[i],
+            SimulationStateElement::EquipmentPowerConsumption(0)
+        );
+        let mut state = header.take_values().unwrap();
+        stove.set_power_consumption(&mut state, 1500.);
+        assert_eq!(stove.power_consumption(&state), Some(1500.));
+    }
+}
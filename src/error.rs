@@ -0,0 +1,521 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+/// The errors that can be found when reading, writing or
+/// querying a [`SimpleModel`](crate::SimpleModel).
+///
+/// Errors found while parsing carry the `line` and `column` (both
+/// starting at 1) of the source in which they were found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimpleModelError {
+    /// A file could not be read or written
+    IO {
+        /// The name of the file
+        filename: String,
+        /// What went wrong
+        message: String,
+    },
+
+    /// The source does not follow the SIMPLE syntax (e.g., a missing
+    /// comma or an unexpected token)
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// A value that was meant to be a number could not be
+    /// read as such (e.g., `12.` when expecting an integer)
+    BadNumber {
+        line: usize,
+        column: usize,
+        /// The value, as written in the source
+        value: String,
+        /// What kind of number was expected (e.g., `"float"`)
+        expected: String,
+        /// The type of the object being read, if known
+        object_type: Option<&'static str>,
+        /// The name of the object being read, if known
+        object_name: Option<String>,
+        /// The field being read, if known
+        field: Option<&'static str>,
+    },
+
+    /// A value has the wrong type (e.g., a number where a
+    /// `String` is expected, or a `Boundary` where an
+    /// `Infiltration` is expected)
+    InvalidValue {
+        line: usize,
+        column: usize,
+        /// What was expected
+        expected: String,
+        /// What was found
+        found: String,
+        /// The type of the object being read, if known
+        object_type: Option<&'static str>,
+        /// The name of the object being read, if known
+        object_name: Option<String>,
+        /// The field being read, if known
+        field: Option<&'static str>,
+    },
+
+    /// An object type—or an enum variant—that does not exist
+    UnknownObjectType {
+        line: usize,
+        column: usize,
+        object_type: String,
+    },
+
+    /// A field that does not exist in a certain object type
+    UnknownField {
+        line: usize,
+        column: usize,
+        object_type: String,
+        field: String,
+    },
+
+    /// A mandatory field was not given a value
+    MissingField {
+        line: usize,
+        column: usize,
+        object_type: String,
+        /// The name of the object, if known
+        object_name: Option<String>,
+        field: String,
+    },
+
+    /// A field that cannot be empty (e.g., a list of layers)
+    /// was given no elements
+    EmptyField {
+        line: usize,
+        column: usize,
+        object_type: String,
+        /// The name of the object, if known
+        object_name: Option<String>,
+        field: String,
+    },
+
+    /// An object references—by name—another object that
+    /// is not in the model
+    UnknownReference {
+        line: usize,
+        column: usize,
+        /// The type of the referenced object
+        reference_type: String,
+        /// The name of the referenced object
+        reference_name: String,
+        /// The type of the object being read, if known
+        object_type: Option<&'static str>,
+        /// The name of the object being read, if known
+        object_name: Option<String>,
+        /// The field being read, if known
+        field: Option<&'static str>,
+    },
+
+    /// A `use` statement (e.g., `use some_db::concrete as "concrete";`),
+    /// which is not supported yet. The objects it refers to are not loaded.
+    UnsupportedUse {
+        line: usize,
+        column: usize,
+        /// The statement, without `use` and `;`
+        statement: String,
+    },
+
+    /// A geometry that cannot be built (e.g., a polygon with
+    /// collinear vertices)
+    InvalidGeometry {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+
+    /// Objects that reference each other (e.g., `A` references `B`,
+    /// which references `A`), so none of them can be built
+    ReferenceCycle {
+        /// The `(object_type, object_name, line)` of the objects in the
+        /// cycle. Each of them references the next one, and the last one
+        /// references the first one.
+        objects: Vec<(String, String, usize)>,
+    },
+
+    /// An object was given values that are inconsistent with each
+    /// other (e.g., a different number of mixture components and fractions)
+    InvalidProperty {
+        object_type: String,
+        object_name: String,
+        message: String,
+    },
+
+    /// An object was requested by its position in the model (or
+    /// in a list of objects), but there is no object there
+    IndexOutOfBounds {
+        object_type: String,
+        index: usize,
+        /// The number of objects available
+        len: usize,
+    },
+
+    /// An optional field was queried, but it has not been
+    /// assigned a value
+    UnassignedField {
+        object_type: String,
+        object_name: String,
+        field: String,
+    },
+
+    /// A [`SimulationStateElement`](crate::SimulationStateElement) was
+    /// requested, but it is not in the
+    /// [`SimulationStateHeader`](crate::SimulationStateHeader)
+    MissingStateElement {
+        /// The element, as debug-printed (e.g., `SpaceDryBulbTemperature(2)`)
+        element: String,
+    },
+
+    /// A snapshot of a [`SimulationState`](crate::SimulationState) cannot
+    /// be read, or it does not match the current
+    /// [`SimulationStateHeader`](crate::SimulationStateHeader)
+    InvalidSnapshot {
+        /// What went wrong
+        message: String,
+    },
+}
+
+impl SimpleModelError {
+    /// Returns the line and column where the error
+    /// was found, if any
+    pub fn position(&self) -> Option<(usize, usize)> {
+        match self {
+            Self::Syntax { line, column, .. }
+            | Self::BadNumber { line, column, .. }
+            | Self::InvalidValue { line, column, .. }
+            | Self::UnknownObjectType { line, column, .. }
+            | Self::UnknownField { line, column, .. }
+            | Self::MissingField { line, column, .. }
+            | Self::EmptyField { line, column, .. }
+            | Self::UnknownReference { line, column, .. }
+            | Self::UnsupportedUse { line, column, .. }
+            | Self::InvalidGeometry { line, column, .. } => Some((*line, *column)),
+            Self::IO { .. }
+            | Self::ReferenceCycle { .. }
+            | Self::InvalidProperty { .. }
+            | Self::IndexOutOfBounds { .. }
+            | Self::UnassignedField { .. }
+            | Self::MissingStateElement { .. }
+            | Self::InvalidSnapshot { .. } => None,
+        }
+    }
+
+    /// Returns the line where the error was found, if any
+    pub fn line(&self) -> Option<usize> {
+        self.position().map(|(line, _)| line)
+    }
+
+    /// Moves the column of errors found in the first line of an
+    /// object that was parsed from a slice of a larger source.
+    ///
+    /// Objects are parsed from slices that start somewhere in the
+    /// middle of a line (e.g., right after `Material`), so the columns
+    /// found in their first `line` are counted from the beginning of the
+    /// slice. This function adds the `offset` of the slice within that line.
+    #[doc(hidden)]
+    pub fn shift_column(mut self, first_line: usize, offset: usize) -> Self {
+        match &mut self {
+            Self::Syntax { line, column, .. }
+            | Self::BadNumber { line, column, .. }
+            | Self::InvalidValue { line, column, .. }
+            | Self::UnknownObjectType { line, column, .. }
+            | Self::UnknownField { line, column, .. }
+            | Self::MissingField { line, column, .. }
+            | Self::EmptyField { line, column, .. }
+            | Self::UnknownReference { line, column, .. }
+            | Self::UnsupportedUse { line, column, .. }
+            | Self::InvalidGeometry { line, column, .. } => {
+                if *line == first_line {
+                    *column += offset;
+                }
+            }
+            Self::IO { .. }
+            | Self::ReferenceCycle { .. }
+            | Self::InvalidProperty { .. }
+            | Self::IndexOutOfBounds { .. }
+            | Self::UnassignedField { .. }
+            | Self::MissingStateElement { .. }
+            | Self::InvalidSnapshot { .. } => {}
+        }
+        self
+    }
+
+    /// Records the object and field that were being read when a
+    /// [`SimpleModelError::BadNumber`], [`SimpleModelError::InvalidValue`]
+    /// or [`SimpleModelError::UnknownReference`] was found.
+    ///
+    /// Errors that already know where they were found (e.g., because
+    /// they come from an object nested in the field) are not modified.
+    #[doc(hidden)]
+    pub fn in_field(
+        mut self,
+        in_type: &'static str,
+        in_name: &Option<String>,
+        in_field: &'static str,
+    ) -> Self {
+        match &mut self {
+            Self::BadNumber {
+                object_type,
+                object_name,
+                field,
+                ..
+            }
+            | Self::InvalidValue {
+                object_type,
+                object_name,
+                field,
+                ..
+            }
+            | Self::UnknownReference {
+                object_type,
+                object_name,
+                field,
+                ..
+            } if object_type.is_none() => {
+                *object_type = Some(in_type);
+                *object_name = in_name.clone();
+                *field = Some(in_field);
+            }
+            _ => {}
+        }
+        self
+    }
+
+    /// Describes where a [`SimpleModelError::BadNumber`],
+    /// [`SimpleModelError::InvalidValue`] or [`SimpleModelError::UnknownReference`]
+    /// was found (e.g., ` (in field 'volume' of Space 'Kitchen')`)
+    fn context(
+        object_type: &Option<&str>,
+        object_name: &Option<String>,
+        field: &Option<&str>,
+    ) -> String {
+        match (object_type, object_name, field) {
+            (Some(t), Some(n), Some(f)) => format!(" (in field '{}' of {} '{}')", f, t, n),
+            (Some(t), None, Some(f)) => format!(" (in field '{}' of {})", f, t),
+            _ => String::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for SimpleModelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some((line, column)) = self.position() {
+            write!(f, "Error [in line {}, column {}]: ", line, column)?;
+        }
+        match self {
+            Self::IO { filename, message } => {
+                write!(f, "Error [in file '{}']: {}", filename, message)
+            }
+            Self::Syntax { message, .. } | Self::InvalidGeometry { message, .. } => {
+                write!(f, "{}", message)
+            }
+            Self::BadNumber {
+                value,
+                expected,
+                object_type,
+                object_name,
+                field,
+                ..
+            } => write!(
+                f,
+                "value '{}' cannot be read as a {}{}",
+                value,
+                expected,
+                Self::context(object_type, object_name, field)
+            ),
+            Self::InvalidValue {
+                expected,
+                found,
+                object_type,
+                object_name,
+                field,
+                ..
+            } => write!(
+                f,
+                "expecting {}... found {}{}",
+                expected,
+                found,
+                Self::context(object_type, object_name, field)
+            ),
+            Self::UnsupportedUse { statement, .. } => write!(
+                f,
+                "'use' statements are not supported yet, so 'use {};' was not loaded",
+                statement
+            ),
+            Self::UnknownObjectType { object_type, .. } => {
+                write!(f, "Unknown object type '{}'", object_type)
+            }
+            Self::UnknownField {
+                object_type, field, ..
+            } => write!(
+                f,
+                "{} does not have a field called '{}'",
+                object_type, field
+            ),
+            Self::MissingField {
+                object_type,
+                object_name,
+                field,
+                ..
+            } => match object_name {
+                Some(name) => write!(
+                    f,
+                    "{} called '{}' does not have a '{}' field",
+                    object_type, name, field
+                ),
+                None => write!(f, "{} does not have a '{}' field", object_type, field),
+            },
+            Self::EmptyField {
+                object_type,
+                object_name,
+                field,
+                ..
+            } => match object_name {
+                Some(name) => write!(
+                    f,
+                    "field '{}' of {} called '{}' cannot be empty",
+                    field, object_type, name
+                ),
+                None => write!(f, "field '{}' of {} cannot be empty", field, object_type),
+            },
+            Self::UnknownReference {
+                reference_type,
+                reference_name,
+                object_type,
+                object_name,
+                field,
+                ..
+            } => write!(
+                f,
+                "Could not find {} '{}'{}",
+                reference_type,
+                reference_name,
+                Self::context(object_type, object_name, field)
+            ),
+            Self::ReferenceCycle { objects } => {
+                write!(f, "Error: objects reference each other in a cycle: ")?;
+                for (object_type, object_name, line) in objects {
+                    write!(
+                        f,
+                        "{} '{}' (line {}) references ",
+                        object_type, object_name, line
+                    )?;
+                }
+                let (object_type, object_name, _) = &objects[0];
+                write!(f, "{} '{}'", object_type, object_name)
+            }
+            Self::InvalidProperty {
+                object_type,
+                object_name,
+                message,
+            } => write!(
+                f,
+                "{} called '{}' is invalid: {}",
+                object_type, object_name, message
+            ),
+            Self::IndexOutOfBounds {
+                object_type,
+                index,
+                len,
+            } => write!(
+                f,
+                "there is no {} #{} (there are only {})",
+                object_type, index, len
+            ),
+            Self::UnassignedField {
+                object_type,
+                object_name,
+                field,
+            } => write!(
+                f,
+                "{} called '{}' has not been assigned any value for field '{}'",
+                object_type, object_name, field
+            ),
+            Self::MissingStateElement { element } => write!(
+                f,
+                "element '{}' is not in the SimulationStateHeader",
+                element
+            ),
+            Self::InvalidSnapshot { message } => write!(f, "Invalid snapshot: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SimpleModelError {}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_shift_column() {
+        let e = SimpleModelError::Syntax {
+            line: 3,
+            column: 2,
+            message: "oops".to_string(),
+        };
+        assert_eq!(e.clone().shift_column(3, 10).position(), Some((3, 12)));
+        assert_eq!(e.shift_column(1, 10).position(), Some((3, 2)));
+    }
+
+    #[test]
+    fn test_display() {
+        let e = SimpleModelError::UnknownReference {
+            line: 4,
+            column: 15,
+            reference_type: "Substance".to_string(),
+            reference_name: "the substance".to_string(),
+            object_type: None,
+            object_name: None,
+            field: None,
+        };
+        assert_eq!(
+            e.to_string(),
+            "Error [in line 4, column 15]: Could not find Substance 'the substance'"
+        );
+        let e = e.in_field("Material", &Some("the material".to_string()), "substance");
+        assert_eq!(
+            e.to_string(),
+            "Error [in line 4, column 15]: Could not find Substance 'the substance' (in field 'substance' of Material 'the material')"
+        );
+        // Errors that already know where they were found are kept
+        let e = e.in_field("Construction", &None, "materials");
+        assert!(e.to_string().ends_with("of Material 'the material')"));
+
+        let e = SimpleModelError::UnassignedField {
+            object_type: "Space".to_string(),
+            object_name: "Kitchen".to_string(),
+            field: "volume".to_string(),
+        };
+        assert_eq!(
+            e.to_string(),
+            "Space called 'Kitchen' has not been assigned any value for field 'volume'"
+        );
+    }
+}
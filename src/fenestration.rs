@@ -28,6 +28,8 @@ use std::rc::Rc;
 use crate::boundary::Boundary;
 use crate::construction::Construction;
 use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::shading::ShadingDevice;
 use crate::simulation_state::SimulationStateHeader;
 use crate::simulation_state_element::{SimulationStateElement, StateElementField};
 
@@ -49,6 +51,7 @@ pub enum FenestrationType {
 /// It can be of any Construction and it does not need to be
 /// a hole in another surface.
 #[derive(ObjectIO, ObjectAPI, Clone)]
+#[api_methods(area, is_operable)]
 pub struct Fenestration {
     /// The name of the sub surface
     pub name: String,
@@ -70,9 +73,12 @@ pub struct Fenestration {
     /// It it a window or a door, or...?
     pub fenestration_type: FenestrationType,
 
-    // The index of the Shading device attached to the Fenestration
-    // in the shading property of the SimpleModel object
-    //shading: Option<usize>,
+    /// The [`ShadingDevice`] attached to the Fenestration
+    shading: Option<Rc<ShadingDevice>>,
+
+    /// The fraction of an operable Fenestration that is open over
+    /// time (from 0 to 1), when it is not operated by a control script
+    opening_schedule: Option<Rc<Schedule>>,
 
     /// A reference to the Boundary in front of the Fenestration
     front_boundary: Option<Boundary>,
@@ -145,6 +151,70 @@ impl Fenestration {
             FenestrationPositions::Binary => true,
         }
     }
+
+    /// Pushes the operational states of the [`Fenestration`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Fenestration has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::FenestrationOpenFraction(obj_index),
+            0.,
+        );
+        self.set_open_fraction_index(state_index);
+    }
+
+    /// Pushes the physical states of the [`Fenestration`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// The temperatures of the nodes are not included, as their number
+    /// depends on how each simulator discretizes the construction
+    pub(crate) fn push_physical_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Fenestration has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::FenestrationFrontConvectionCoefficient(obj_index),
+            0.,
+        );
+        self.set_front_convection_coefficient_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationBackConvectionCoefficient(obj_index),
+            0.,
+        );
+        self.set_back_convection_coefficient_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationFrontConvectiveHeatFlow(obj_index),
+            0.,
+        );
+        self.set_front_convective_heat_flow_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationBackConvectiveHeatFlow(obj_index),
+            0.,
+        );
+        self.set_back_convective_heat_flow_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationFrontSolarIrradiance(obj_index),
+            0.,
+        );
+        self.set_front_incident_solar_irradiance_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationBackSolarIrradiance(obj_index),
+            0.,
+        );
+        self.set_back_incident_solar_irradiance_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationFrontIRIrradiance(obj_index),
+            0.,
+        );
+        self.set_front_ir_irradiance_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::FenestrationBackIRIrradiance(obj_index),
+            0.,
+        );
+        self.set_back_ir_irradiance_index(state_index);
+    }
 }
 
 impl SimpleModel {
@@ -159,11 +229,7 @@ impl SimpleModel {
         add.set_index(fen_index);
 
         // Push the OpenFraction state, and map into the object
-        let state_index = state.push(
-            SimulationStateElement::FenestrationOpenFraction(fen_index),
-            0.,
-        );
-        add.set_open_fraction_index(state_index);
+        add.push_operational_states(state);
 
         // Add to model, and return a reference
         let add = Rc::new(add);
@@ -0,0 +1,136 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::Float;
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::thermostat::Thermostat;
+use crate::SimpleModelError;
+use std::rc::Rc;
+
+use derive::{GroupMemberAPI, ObjectIO};
+
+/// An air-source heat pump, which heats and/or cools a [`Space`] with
+/// a Coefficient of Performance (COP) that depends on the outdoor temperature.
+///
+/// The COP curves are polynomials of the outdoor dry bulb temperature `T`
+/// (in C), given by their coefficients: `COP = c0 + c1*T + c2*T^2 + ...`
+///
+/// ### Example
+///
+/// ```rs
+/// HVAC::AirSourceHeatPump {
+///     name: "Bedroom heat pump",
+///     target_space: "Bedroom",
+///     max_heating_power: 3500,
+///     max_cooling_power: 3000,
+///     heating_cop_coefficients: [3.0, 0.05],
+///     cooling_cop_coefficients: [5.5, -0.08]
+/// }
+/// ```
+#[derive(Clone, ObjectIO, GroupMemberAPI)]
+pub struct AirSourceHeatPump {
+    /// The name of the system
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The [`Space`] that this [`AirSourceHeatPump`] heats and/or
+    /// cools
+    target_space: Option<Rc<Space>>,
+
+    /// Max heating power
+    max_heating_power: Option<Float>,
+
+    /// Max cooling power
+    max_cooling_power: Option<Float>,
+
+    /// The coefficients of the heating COP curve
+    heating_cop_coefficients: Option<Vec<Float>>,
+
+    /// The coefficients of the cooling COP curve
+    cooling_cop_coefficients: Option<Vec<Float>>,
+
+    /// The [`Thermostat`] that holds the setpoints
+    /// used for controlling this system
+    thermostat: Option<Rc<Thermostat>>,
+
+    /// Whether the system can be used (1) or
+    /// not (0) over time
+    availability: Option<Rc<Schedule>>,
+
+    /// The electricity consumed, in W
+    #[operational("power_consumption")]
+    heating_cooling_consumption: StateElementField,
+
+    /// The heat delivered to the space, in W. It
+    /// is negative when cooling.
+    #[operational]
+    delivered_heat: StateElementField,
+
+    /// The fraction of the max power at which the
+    /// system is working (from 0 to 1)
+    #[operational]
+    part_load_ratio: StateElementField,
+}
+
+/// Evaluates a polynomial given by its coefficients
+fn polynomial(coefficients: &[Float], x: Float) -> Float {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
+
+impl AirSourceHeatPump {
+    pub fn wrap(self) -> crate::hvac::HVAC {
+        crate::hvac::HVAC::AirSourceHeatPump(std::rc::Rc::new(self))
+    }
+
+    /// Pushes the operational states of the [`AirSourceHeatPump`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("AirSourceHeatPump has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
+            0.,
+        );
+        self.set_heating_cooling_consumption_index(state_index);
+        let state_index = state.push(SimulationStateElement::HVACDeliveredHeat(obj_index), 0.);
+        self.set_delivered_heat_index(state_index);
+        let state_index = state.push(SimulationStateElement::HVACPartLoadRatio(obj_index), 0.);
+        self.set_part_load_ratio_index(state_index);
+    }
+
+    /// The heating COP at a certain outdoor temperature (in C)
+    pub fn heating_cop(&self, outdoor_temperature: Float) -> Result<Float, SimpleModelError> {
+        let coefficients = self.heating_cop_coefficients()?;
+        Ok(polynomial(coefficients, outdoor_temperature))
+    }
+
+    /// The cooling COP at a certain outdoor temperature (in C)
+    pub fn cooling_cop(&self, outdoor_temperature: Float) -> Result<Float, SimpleModelError> {
+        let coefficients = self.cooling_cop_coefficients()?;
+        Ok(polynomial(coefficients, outdoor_temperature))
+    }
+}
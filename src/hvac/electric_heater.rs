@@ -21,8 +21,11 @@ SOFTWARE.
 use crate::Float;
 
 use crate::model::SimpleModel;
-use crate::simulation_state_element::StateElementField;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
 use crate::space::Space;
+use crate::thermostat::Thermostat;
 use std::rc::Rc;
 
 use derive::{GroupMemberAPI, ObjectIO};
@@ -42,6 +45,14 @@ pub struct ElectricHeater {
     /// Max heating power
     max_heating_power: Option<Float>,
 
+    /// The [`Thermostat`] that holds the setpoints
+    /// used for controlling this system
+    thermostat: Option<Rc<Thermostat>>,
+
+    /// Whether the heater can be used (1) or
+    /// not (0) over time
+    availability: Option<Rc<Schedule>>,
+
     #[operational("power_consumption")]
     heating_cooling_consumption: StateElementField,
 }
@@ -50,4 +61,17 @@ impl ElectricHeater {
     pub fn wrap(self) -> crate::hvac::HVAC {
         crate::hvac::HVAC::ElectricHeater(std::rc::Rc::new(self))
     }
+
+    /// Pushes the operational states of the [`ElectricHeater`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("ElectricHeater has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
+            0.,
+        );
+        self.set_heating_cooling_consumption_index(state_index);
+    }
 }
@@ -0,0 +1,120 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::Float;
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::thermostat::Thermostat;
+use std::rc::Rc;
+
+use derive::{GroupMemberAPI, ObjectIO};
+
+/// A fan-coil or Variable Refrigerant Flow (VRF) system, with
+/// an indoor unit in each of the `Space`s it serves.
+///
+/// ### Example
+///
+/// ```rs
+/// HVAC::FanCoil {
+///     name: "Office VRF",
+///     target_spaces: ["Office 1", "Office 2"],
+///     max_heating_power: 8000,
+///     max_cooling_power: 7000,
+///     heating_cop: 3.8,
+///     cooling_cop: 3.2,
+///     fan_power: 150
+/// }
+/// ```
+#[derive(Clone, ObjectIO, GroupMemberAPI)]
+pub struct FanCoil {
+    /// The name of the system
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The `Space`s that this `FanCoil` heats and/or
+    /// cools
+    pub target_spaces: Vec<Rc<Space>>,
+
+    /// Max heating power
+    max_heating_power: Option<Float>,
+
+    /// Max cooling power
+    max_cooling_power: Option<Float>,
+
+    /// The Coefficient of Performance when heating
+    heating_cop: Option<Float>,
+
+    /// The Coefficient of Performance when cooling
+    cooling_cop: Option<Float>,
+
+    /// The power consumed by the fans of all
+    /// the indoor units, in W
+    fan_power: Option<Float>,
+
+    /// The [`Thermostat`] that holds the setpoints
+    /// used for controlling this system
+    thermostat: Option<Rc<Thermostat>>,
+
+    /// Whether the system can be used (1) or
+    /// not (0) over time
+    availability: Option<Rc<Schedule>>,
+
+    /// The electricity consumed, in W
+    #[operational("power_consumption")]
+    heating_cooling_consumption: StateElementField,
+
+    /// The heat delivered to the spaces, in W. It
+    /// is negative when cooling.
+    #[operational]
+    delivered_heat: StateElementField,
+
+    /// The fraction of the max power at which the
+    /// system is working (from 0 to 1)
+    #[operational]
+    part_load_ratio: StateElementField,
+}
+
+impl FanCoil {
+    pub fn wrap(self) -> crate::hvac::HVAC {
+        crate::hvac::HVAC::FanCoil(std::rc::Rc::new(self))
+    }
+
+    /// Pushes the operational states of the [`FanCoil`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("FanCoil has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
+            0.,
+        );
+        self.set_heating_cooling_consumption_index(state_index);
+        let state_index = state.push(SimulationStateElement::HVACDeliveredHeat(obj_index), 0.);
+        self.set_delivered_heat_index(state_index);
+        let state_index = state.push(SimulationStateElement::HVACPartLoadRatio(obj_index), 0.);
+        self.set_part_load_ratio_index(state_index);
+    }
+}
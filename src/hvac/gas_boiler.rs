@@ -0,0 +1,127 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::Float;
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::thermostat::Thermostat;
+use crate::SimpleModelError;
+use std::rc::Rc;
+
+use derive::{GroupMemberAPI, ObjectIO};
+
+/// A gas boiler that feeds radiators placed in one or
+/// more [`Space`]s. It can only heat.
+///
+/// ### Example
+///
+/// ```rs
+/// HVAC::GasBoiler {
+///     name: "Main boiler",
+///     target_spaces: ["Kitchen", "Living room"],
+///     radiator_powers: [1500, 2500],
+///     max_heating_power: 24000,
+///     efficiency: 0.9
+/// }
+/// ```
+#[derive(Clone, ObjectIO, GroupMemberAPI)]
+pub struct GasBoiler {
+    /// The name of the system
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The `Space`s in which the radiators fed by
+    /// this `GasBoiler` are located
+    pub target_spaces: Vec<Rc<Space>>,
+
+    /// The nominal heat output of the radiator in each
+    /// of the `target_spaces`, in the same order
+    radiator_powers: Option<Vec<Float>>,
+
+    /// Max heating power
+    max_heating_power: Option<Float>,
+
+    /// The ratio between the heat delivered and the
+    /// energy of the gas consumed (from 0 to 1)
+    efficiency: Option<Float>,
+
+    /// The [`Thermostat`] that holds the setpoints
+    /// used for controlling this system
+    thermostat: Option<Rc<Thermostat>>,
+
+    /// Whether the system can be used (1) or
+    /// not (0) over time
+    availability: Option<Rc<Schedule>>,
+
+    /// The gas consumed, in W
+    #[operational("power_consumption")]
+    heating_cooling_consumption: StateElementField,
+
+    /// The heat delivered by the radiators, in W
+    #[operational]
+    delivered_heat: StateElementField,
+
+    /// The fraction of the max power at which the
+    /// system is working (from 0 to 1)
+    #[operational]
+    part_load_ratio: StateElementField,
+}
+
+impl GasBoiler {
+    pub fn wrap(self) -> crate::hvac::HVAC {
+        crate::hvac::HVAC::GasBoiler(std::rc::Rc::new(self))
+    }
+
+    /// Pushes the operational states of the [`GasBoiler`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("GasBoiler has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
+            0.,
+        );
+        self.set_heating_cooling_consumption_index(state_index);
+        let state_index = state.push(SimulationStateElement::HVACDeliveredHeat(obj_index), 0.);
+        self.set_delivered_heat_index(state_index);
+        let state_index = state.push(SimulationStateElement::HVACPartLoadRatio(obj_index), 0.);
+        self.set_part_load_ratio_index(state_index);
+    }
+
+    /// The power of gas that needs to be consumed in order
+    /// to deliver a certain amount of heat
+    pub fn gas_consumption(&self, delivered_heat: Float) -> Result<Float, SimpleModelError> {
+        let efficiency = *self.efficiency()?;
+        if efficiency <= 0.0 || efficiency > 1.0 {
+            return Err(SimpleModelError::InvalidProperty {
+                object_type: "GasBoiler".to_string(),
+                object_name: self.name.clone(),
+                message: format!("efficiency must be between 0 and 1... found {}", efficiency),
+            });
+        }
+        Ok(delivered_heat / efficiency)
+    }
+}
@@ -21,8 +21,11 @@ SOFTWARE.
 use crate::Float;
 
 use crate::model::SimpleModel;
-use crate::simulation_state_element::StateElementField;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
 use crate::space::Space;
+use crate::thermostat::Thermostat;
 use std::rc::Rc;
 
 use derive::{GroupMemberAPI, ObjectIO};
@@ -47,6 +50,14 @@ pub struct IdealHeaterCooler {
     /// Max cooling power
     max_cooling_power: Option<Float>,
 
+    /// The [`Thermostat`] that holds the setpoints
+    /// used for controlling this system
+    thermostat: Option<Rc<Thermostat>>,
+
+    /// Whether the system can be used (1) or
+    /// not (0) over time
+    availability: Option<Rc<Schedule>>,
+
     #[operational("power_consumption")]
     heating_cooling_consumption: StateElementField,
 }
@@ -55,4 +66,17 @@ impl IdealHeaterCooler {
     pub fn wrap(self) -> crate::hvac::HVAC {
         crate::hvac::HVAC::IdealHeaterCooler(std::rc::Rc::new(self))
     }
+
+    /// Pushes the operational states of the [`IdealHeaterCooler`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("IdealHeaterCooler has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
+            0.,
+        );
+        self.set_heating_cooling_consumption_index(state_index);
+    }
 }
@@ -18,10 +18,16 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+mod air_source_heat_pump;
 mod electric_heater;
+mod fan_coil;
+mod gas_boiler;
 mod ideal_heater_cooler;
 
+pub use crate::hvac::air_source_heat_pump::AirSourceHeatPump;
 pub use crate::hvac::electric_heater::ElectricHeater;
+pub use crate::hvac::fan_coil::FanCoil;
+pub use crate::hvac::gas_boiler::GasBoiler;
 pub use crate::hvac::ideal_heater_cooler::IdealHeaterCooler;
 
 use crate::model::SimpleModel;
@@ -39,38 +45,98 @@ pub enum HVAC {
     /// An electric heater, it can only
     /// heat.
     ElectricHeater(Rc<ElectricHeater>),
+
+    /// An air-source heat pump, whose COP depends
+    /// on the outdoor temperature
+    AirSourceHeatPump(Rc<AirSourceHeatPump>),
+
+    /// A gas boiler feeding radiators in one or
+    /// more spaces. It can only heat.
+    GasBoiler(Rc<GasBoiler>),
+
+    /// A fan-coil or VRF system serving one
+    /// or more spaces
+    FanCoil(Rc<FanCoil>),
 }
 
 use crate::simulation_state::SimulationStateHeader;
-use crate::simulation_state_element::SimulationStateElement;
+
+impl HVAC {
+    /// Gets the [`Space`](crate::Space)s served by this [`HVAC`]
+    pub fn target_spaces(&self) -> Vec<Rc<crate::Space>> {
+        match self {
+            HVAC::IdealHeaterCooler(hvac) => hvac.target_spaces.clone(),
+            HVAC::ElectricHeater(hvac) => hvac.target_space().into_iter().cloned().collect(),
+            HVAC::AirSourceHeatPump(hvac) => hvac.target_space().into_iter().cloned().collect(),
+            HVAC::GasBoiler(hvac) => hvac.target_spaces.clone(),
+            HVAC::FanCoil(hvac) => hvac.target_spaces.clone(),
+        }
+    }
+
+    /// Changes the indices of all the [`SimulationStateElement`] of
+    /// this [`HVAC`] within the `SimulationState` (see the
+    /// `map_state_indices()` method of each kind of system)
+    pub fn map_state_indices(&self, f: &dyn Fn(usize) -> Option<usize>) {
+        match self {
+            HVAC::IdealHeaterCooler(hvac) => hvac.map_state_indices(f),
+            HVAC::ElectricHeater(hvac) => hvac.map_state_indices(f),
+            HVAC::AirSourceHeatPump(hvac) => hvac.map_state_indices(f),
+            HVAC::GasBoiler(hvac) => hvac.map_state_indices(f),
+            HVAC::FanCoil(hvac) => hvac.map_state_indices(f),
+        }
+    }
+
+    /// Pushes the operational states of the [`HVAC`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        match self {
+            HVAC::IdealHeaterCooler(hvac) => hvac.push_operational_states(state),
+            HVAC::ElectricHeater(hvac) => hvac.push_operational_states(state),
+            HVAC::AirSourceHeatPump(hvac) => hvac.push_operational_states(state),
+            HVAC::GasBoiler(hvac) => hvac.push_operational_states(state),
+            HVAC::FanCoil(hvac) => hvac.push_operational_states(state),
+        }
+    }
+
+    /// Sets the index of the [`HVAC`] in its container Vector
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system is shared (i.e., if there are other
+    /// references to it)
+    fn set_index(&mut self, index: usize) {
+        fn get_mut<T>(hvac: &mut Rc<T>) -> &mut T {
+            Rc::get_mut(hvac).expect("Could not borrow HVAC as mut when adding")
+        }
+        match self {
+            HVAC::IdealHeaterCooler(hvac) => {
+                get_mut(hvac).set_index(index);
+            }
+            HVAC::ElectricHeater(hvac) => {
+                get_mut(hvac).set_index(index);
+            }
+            HVAC::AirSourceHeatPump(hvac) => {
+                get_mut(hvac).set_index(index);
+            }
+            HVAC::GasBoiler(hvac) => {
+                get_mut(hvac).set_index(index);
+            }
+            HVAC::FanCoil(hvac) => {
+                get_mut(hvac).set_index(index);
+            }
+        }
+    }
+}
 
 impl SimpleModel {
     /// Adds a [`HVAC`] to the [`SimpleModel`]
     pub fn add_hvac(&mut self, mut add: HVAC, state: &mut SimulationStateHeader) -> HVAC {
         // Check the index of this object
         let obj_index = self.hvacs.len();
-        match &mut add {
-            HVAC::ElectricHeater(hvac) => {
-                let hvac =
-                    Rc::get_mut(hvac).expect("Could not borrow ElectricHeater as mut when adding");
-                hvac.set_index(obj_index);
-                let state_index = state.push(
-                    SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
-                    0.,
-                );
-                hvac.set_heating_cooling_consumption_index(state_index);
-            }
-            HVAC::IdealHeaterCooler(hvac) => {
-                let hvac = Rc::get_mut(hvac)
-                    .expect("Could not borrow IdealHeaterCooler as mut when adding");
-                hvac.set_index(obj_index);
-                let state_index = state.push(
-                    SimulationStateElement::HeatingCoolingPowerConsumption(obj_index),
-                    0.,
-                );
-                hvac.set_heating_cooling_consumption_index(state_index);
-            }
-        }
+        add.set_index(obj_index);
+
+        // Push the states, and map into the object
+        add.push_operational_states(state);
 
         // Add to model, and return a reference
         self.hvacs.push(add.clone());
@@ -85,6 +151,7 @@ impl SimpleModel {
 #[cfg(test)]
 mod testing {
     use super::*;
+    use crate::simulation_state_element::SimulationStateElement;
     use crate::space::Space;
     use std::rc::Rc;
 
@@ -113,4 +180,97 @@ mod testing {
             panic!("Definitely NOT an electric heater....!")
         }
     }
+
+    #[test]
+    fn test_hvac_variants() {
+        let src = b"
+Space { name: \"kitchen\" }
+Space { name: \"living room\" }
+
+HVAC::AirSourceHeatPump {
+    name: \"heat pump\",
+    target_space: \"kitchen\",
+    heating_cop_coefficients: [3.0, 0.05],
+    cooling_cop_coefficients: [5.5, -0.08, 0.001]
+}
+
+HVAC::GasBoiler {
+    name: \"boiler\",
+    target_spaces: [\"kitchen\", \"living room\"],
+    radiator_powers: [1500, 2500],
+    efficiency: 0.8
+}
+
+HVAC::FanCoil {
+    name: \"vrf\",
+    target_spaces: [\"kitchen\", \"living room\"],
+    heating_cop: 3.8,
+    fan_power: 150
+}
+";
+        let (model, mut header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        assert_eq!(model.hvacs.len(), 3);
+
+        // Power, delivered heat and part load ratio for each one of them
+        assert_eq!(header.n_operational(), 9);
+        for (i, e) in header.elements[..9].chunks(3).enumerate() {
+            assert_eq!(
+                e,
+                &[
+                    SimulationStateElement::HeatingCoolingPowerConsumption(i),
+                    SimulationStateElement::HVACDeliveredHeat(i),
+                    SimulationStateElement::HVACPartLoadRatio(i),
+                ]
+            );
+        }
+        let mut state = header.take_values().unwrap();
+
+        if let HVAC::AirSourceHeatPump(hp) = &model.hvacs[0] {
+            assert!(Rc::ptr_eq(hp.target_space().unwrap(), &model.spaces[0]));
+            assert!((hp.heating_cop(10.).unwrap() - 3.5).abs() < 1e-9);
+            assert!((hp.cooling_cop(30.).unwrap() - 4.0).abs() < 1e-9);
+            hp.set_delivered_heat(&mut state, 1200.);
+            assert_eq!(hp.delivered_heat(&state), Some(1200.));
+        } else {
+            panic!("Expecting an AirSourceHeatPump")
+        }
+
+        if let HVAC::GasBoiler(boiler) = &model.hvacs[1] {
+            assert_eq!(boiler.target_spaces.len(), 2);
+            assert_eq!(boiler.radiator_powers().unwrap(), &vec![1500., 2500.]);
+            assert!((boiler.gas_consumption(800.).unwrap() - 1000.).abs() < 1e-9);
+            boiler.set_part_load_ratio(&mut state, 0.5);
+            assert_eq!(boiler.part_load_ratio(&state), Some(0.5));
+        } else {
+            panic!("Expecting a GasBoiler")
+        }
+
+        if let HVAC::FanCoil(vrf) = &model.hvacs[2] {
+            assert_eq!(*vrf.heating_cop().unwrap(), 3.8);
+            assert!(vrf.cooling_cop().is_err());
+            assert_eq!(vrf.heating_cooling_consumption(&state), Some(0.));
+        } else {
+            panic!("Expecting a FanCoil")
+        }
+
+        // Check that the API can reach them
+        let model = Rc::new(model);
+        let state = Rc::new(std::cell::RefCell::new(state));
+        let mut engine = rhai::Engine::new();
+        crate::rhai_api::register_control_api(&mut engine, &model, &state, true);
+        let heat: crate::Float = engine
+            .eval(
+                "
+            let boiler = hvac(\"boiler\");
+            boiler.delivered_heat = 2100.0;
+            let vrf = hvac(2);
+            vrf.part_load_ratio = 0.25;
+            boiler.delivered_heat + hvac(\"vrf\").part_load_ratio
+        ",
+            )
+            .unwrap();
+        assert!((heat - 2100.25).abs() < 1e-9);
+    }
 }
@@ -33,20 +33,33 @@ type Float = f64;
 pub mod rhai_api;
 pub mod scanner;
 
+mod error;
+pub use error::SimpleModelError;
+
 mod simulation_state;
 pub use simulation_state::{SimulationState, SimulationStateHeader};
 
+mod snapshot;
+
+mod recorder;
+pub use recorder::{Aggregation, AggregationPeriod, Recorder};
+
 mod simulation_state_element;
 pub use simulation_state_element::SimulationStateElement;
 
 mod model;
 pub use model::SimpleModel;
 
+mod validation;
+pub use validation::ValidationIssue;
+
 mod building;
 pub use building::{Building, ShelterClass};
 
 mod construction;
-pub use construction::Construction;
+pub use construction::{
+    Construction, STANDARD_EXTERIOR_FILM_COEFFICIENT, STANDARD_INTERIOR_FILM_COEFFICIENT,
+};
 
 mod material;
 pub use material::Material;
@@ -58,12 +71,36 @@ pub use substance::Substance;
 mod boundary;
 pub use boundary::Boundary;
 
+mod adjacency;
+pub use adjacency::{Adjacency, PartialOverlap, SurfaceBoundaries};
+
 mod fenestration;
 pub use fenestration::{Fenestration, FenestrationPositions, FenestrationType};
 
 mod luminaire;
 pub use luminaire::Luminaire;
 
+mod equipment;
+pub use equipment::{Equipment, EquipmentFuel};
+
+mod thermostat;
+pub use thermostat::Thermostat;
+
+mod ventilation;
+pub use ventilation::Ventilation;
+
+mod air_exchange;
+pub use air_exchange::AirExchange;
+
+pub mod shading;
+pub use shading::ShadingDevice;
+
+mod schedule;
+pub use schedule::{Schedule, ScheduleProfile};
+
+mod occupant;
+pub use occupant::Occupant;
+
 mod space;
 pub use space::Space;
 
@@ -18,6 +18,7 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use crate::model::SimpleModel;
+use crate::schedule::Schedule;
 use crate::simulation_state::SimulationStateHeader;
 use crate::simulation_state_element::{SimulationStateElement, StateElementField};
 use crate::space::Space;
@@ -48,12 +49,31 @@ pub struct Luminaire {
     /// calculation is performed, this can be left empty.
     target_space: Option<Rc<Space>>,
 
+    /// The fraction of the `max_power` that is available
+    /// over time (from 0 to 1)
+    availability: Option<Rc<Schedule>>,
+
     /// The index of the state of the luminaire
     /// in the State array    
     #[operational]
     power_consumption: StateElementField,
 }
 
+impl Luminaire {
+    /// Pushes the operational states of the [`Luminaire`] into
+    /// a [`SimulationStateHeader`], and maps them into the object
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Luminaire has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::LuminairePowerConsumption(obj_index),
+            0.,
+        );
+        self.set_power_consumption_index(state_index);
+    }
+}
+
 impl SimpleModel {
     /// Adds a [`Luminaire`] to the [`SimpleModel`]
     pub fn add_luminaire(
@@ -62,15 +82,11 @@ impl SimpleModel {
         state: &mut SimulationStateHeader,
     ) -> Rc<Luminaire> {
         // Check the index of this object
-        let obj_index = self.fenestrations.len();
+        let obj_index = self.luminaires.len();
         add.set_index(obj_index);
 
         // Push the state, and map into the object
-        let state_index = state.push(
-            SimulationStateElement::LuminairePowerConsumption(obj_index),
-            0.,
-        );
-        add.set_power_consumption_index(state_index);
+        add.push_operational_states(state);
 
         // Add to model, and return a reference
         let add = Rc::new(add);
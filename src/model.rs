@@ -18,19 +18,26 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 use crate::hvac::*;
-use crate::scanner::SimpleScanner;
+use crate::scanner::{ControlScript, MDScanner, SimpleScanner};
+use crate::SimpleModelError;
 use crate::SimulationStateHeader;
 use derive::ObjectIO;
 use std::fs;
 use std::rc::Rc;
 
-use crate::{Building, Construction, Fenestration, Luminaire, Material, Space, Substance, Surface};
+use crate::{
+    AirExchange, Building, Construction, Equipment, Fenestration, Luminaire, Material, Occupant,
+    Schedule, ShadingDevice, Space, Substance, Surface, Thermostat, Ventilation,
+};
 
 #[derive(Default, ObjectIO)]
 pub struct SimpleModel {
     /// The name of the building
     pub name: String,
 
+    /// The schedules, which are referenced by name by other objects
+    pub schedules: Vec<Rc<Schedule>>,
+
     // materiality
     pub substances: Vec<Substance>,
     pub materials: Vec<Rc<Material>>,
@@ -49,17 +56,113 @@ pub struct SimpleModel {
 
     /// Luminaires
     pub luminaires: Vec<Rc<Luminaire>>,
+
+    /// Equipment (e.g., computers, appliances) that
+    /// release heat into the spaces
+    pub equipment: Vec<Rc<Equipment>>,
+
+    /// Thermostats, holding the setpoints of the spaces
+    pub thermostats: Vec<Rc<Thermostat>>,
+
+    /// Mechanical ventilation systems
+    pub ventilations: Vec<Rc<Ventilation>>,
+
+    /// Exchanges of air between spaces
+    pub air_exchanges: Vec<Rc<AirExchange>>,
+
+    /// The blinds, curtains and overhangs that shade the fenestrations
+    pub shading_devices: Vec<Rc<ShadingDevice>>,
+
+    /// The people in the spaces
+    pub occupants: Vec<Rc<Occupant>>,
 }
 
 impl SimpleModel {
-    pub fn from_file(filename: String) -> Result<(Self, SimulationStateHeader), String> {
-        let bytes = match fs::read(filename.clone()) {
-            Ok(v) => v,
-            Err(_) => return Err(format!("Could not read SIMPLE file '{}'", filename)),
-        };
+    /// Reads the bytes of a model file
+    fn read_bytes(filename: String, kind: &str) -> Result<Vec<u8>, SimpleModelError> {
+        fs::read(filename.clone()).map_err(|e| SimpleModelError::IO {
+            filename,
+            message: format!("Could not read {} file: {}", kind, e),
+        })
+    }
+
+    /// Reads a SIMPLE model file.
+    ///
+    /// This is strict: if any object cannot be read, all the
+    /// diagnostics found are returned instead of a model.
+    pub fn from_file(
+        filename: String,
+    ) -> Result<(Self, SimulationStateHeader), Vec<SimpleModelError>> {
+        let bytes = Self::read_bytes(filename, "SIMPLE").map_err(|e| vec![e])?;
+        let mut scanner = SimpleScanner::new(&bytes, 1);
+        scanner.parse_model()
+    }
+
+    /// Reads a SIMPLE model file, skipping the objects that cannot be read.
+    ///
+    /// Returns the (partial) model together with the diagnostics found.
+    /// Fails only if the file cannot be read.
+    pub fn from_file_lenient(
+        filename: String,
+    ) -> Result<(Self, SimulationStateHeader, Vec<SimpleModelError>), SimpleModelError> {
+        let bytes = Self::read_bytes(filename, "SIMPLE")?;
         let mut scanner = SimpleScanner::new(&bytes, 1);
+        Ok(scanner.parse_model_lenient())
+    }
+
+    /// Reads a literate Markdown model file (i.e., a `.md` file whose
+    /// ` ```rust ` blocks contain the model and whose ` ```rust control `
+    /// blocks contain control scripts). Just like [`SimpleModel::from_file`],
+    /// this is strict.
+    pub fn from_md_file(
+        filename: String,
+    ) -> Result<(Self, SimulationStateHeader, Vec<ControlScript>), Vec<SimpleModelError>> {
+        let bytes = Self::read_bytes(filename, "Markdown").map_err(|e| vec![e])?;
+        let mut scanner = MDScanner::new(&bytes);
         scanner.parse_model()
     }
+
+    /// Writes the whole [`SimpleModel`] in the same format that is read
+    /// by [`SimpleModel::from_file`].
+    ///
+    /// Objects are written in the order in which they are built when
+    /// reading a model, and references are written by name.
+    pub fn to_spl(&self) -> String {
+        let mut objects: Vec<String> = Vec::new();
+
+        objects.extend(self.schedules.iter().map(|s| s.to_spl()));
+        objects.extend(self.buildings.iter().map(|s| s.to_spl()));
+        objects.extend(self.substances.iter().map(|s| s.to_spl()));
+        objects.extend(self.materials.iter().map(|s| s.to_spl()));
+        objects.extend(self.constructions.iter().map(|s| s.to_spl()));
+        objects.extend(self.spaces.iter().map(|s| s.to_spl()));
+        objects.extend(self.occupants.iter().map(|s| s.to_spl()));
+        objects.extend(self.surfaces.iter().map(|s| s.to_spl()));
+        objects.extend(self.shading_devices.iter().map(|s| s.to_spl()));
+        objects.extend(self.fenestrations.iter().map(|s| s.to_spl()));
+        objects.extend(self.thermostats.iter().map(|s| s.to_spl()));
+        objects.extend(self.hvacs.iter().map(|s| s.to_spl()));
+        objects.extend(self.luminaires.iter().map(|s| s.to_spl()));
+        objects.extend(self.equipment.iter().map(|s| s.to_spl()));
+        objects.extend(self.ventilations.iter().map(|s| s.to_spl()));
+        objects.extend(self.air_exchanges.iter().map(|s| s.to_spl()));
+
+        let mut ret = objects.join("\n\n");
+        ret.push('\n');
+        ret
+    }
+
+    /// Writes the [`SimpleModel`] into a file that can be read
+    /// by [`SimpleModel::from_file`]
+    pub fn write_to(&self, filename: String) -> Result<(), SimpleModelError> {
+        match fs::write(filename.clone(), self.to_spl()) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(SimpleModelError::IO {
+                filename,
+                message: format!("Could not write SIMPLE file: {}", e),
+            }),
+        }
+    }
 }
 
 /***********/
@@ -94,6 +197,10 @@ mod testing {
         let f = std::fs::File::create(&summary_file).unwrap();
         f.set_len(0).unwrap();
 
+        // AirExchange
+        AirExchange::print_doc(dir, &mut summary).unwrap();
+        AirExchange::print_api_doc(dir, &mut summary).unwrap();
+
         // Boundary
         Boundary::print_doc(&dir, &mut summary).unwrap();
 
@@ -103,6 +210,11 @@ mod testing {
         // Construction
         Construction::print_doc(&dir, &mut summary).unwrap();
 
+        // Equipment
+        Equipment::print_doc(dir, &mut summary).unwrap();
+        Equipment::print_api_doc(dir, &mut summary).unwrap();
+        crate::equipment::EquipmentFuel::print_doc(dir, &mut summary).unwrap();
+
         // Fenestration
         Fenestration::print_doc(&dir, &mut summary).unwrap();
         Fenestration::print_api_doc(&dir, &mut summary).unwrap();
@@ -118,6 +230,18 @@ mod testing {
         hvac::IdealHeaterCooler::print_doc(&dir, &mut summary).unwrap();
         hvac::IdealHeaterCooler::print_api_doc(&dir, &mut summary).unwrap();
 
+        summary.push('\t');
+        hvac::AirSourceHeatPump::print_doc(dir, &mut summary).unwrap();
+        hvac::AirSourceHeatPump::print_api_doc(dir, &mut summary).unwrap();
+
+        summary.push('\t');
+        hvac::GasBoiler::print_doc(dir, &mut summary).unwrap();
+        hvac::GasBoiler::print_api_doc(dir, &mut summary).unwrap();
+
+        summary.push('\t');
+        hvac::FanCoil::print_doc(dir, &mut summary).unwrap();
+        hvac::FanCoil::print_api_doc(dir, &mut summary).unwrap();
+
         // Infiltration
         crate::infiltration::Infiltration::print_doc(&dir, &mut summary).unwrap();
 
@@ -128,6 +252,20 @@ mod testing {
         // Material
         Material::print_doc(&dir, &mut summary).unwrap();
 
+        // Occupant
+        Occupant::print_doc(dir, &mut summary).unwrap();
+        Occupant::print_api_doc(dir, &mut summary).unwrap();
+
+        // Schedule
+        Schedule::print_doc(dir, &mut summary).unwrap();
+        crate::schedule::ScheduleProfile::print_doc(dir, &mut summary).unwrap();
+
+        // Shading
+        ShadingDevice::print_doc(dir, &mut summary).unwrap();
+        ShadingDevice::print_api_doc(dir, &mut summary).unwrap();
+        crate::shading::ShadingLocation::print_doc(dir, &mut summary).unwrap();
+        crate::shading::ShadingClass::print_doc(dir, &mut summary).unwrap();
+
         // Space
         Space::print_doc(&dir, &mut summary).unwrap();
         Space::print_api_doc(&dir, &mut summary).unwrap();
@@ -138,13 +276,20 @@ mod testing {
         summary.push_str(&format!("\t"));
         substance::Normal::print_doc(&dir, &mut summary).unwrap();
         substance::Gas::print_doc(&dir, &mut summary).unwrap();
-        
 
         crate::building::ShelterClass::print_doc(&dir, &mut summary).unwrap();
 
         Surface::print_doc(&dir, &mut summary).unwrap();
         Surface::print_api_doc(&dir, &mut summary).unwrap();
 
+        // Thermostat
+        Thermostat::print_doc(dir, &mut summary).unwrap();
+        Thermostat::print_api_doc(dir, &mut summary).unwrap();
+
+        // Ventilation
+        Ventilation::print_doc(dir, &mut summary).unwrap();
+        Ventilation::print_api_doc(dir, &mut summary).unwrap();
+
         let current_summary =
             fs::read_to_string(summary_file.clone()).expect("Could not read summary file");
         let whole_summary = format!("{}\n\n{}", current_summary, summary);
@@ -191,6 +336,182 @@ mod testing {
         }
     }
 
+    #[test]
+    fn test_write_read_round_trip() {
+        let src = b"
+        Building {
+            name: \"Main campus\",
+            n_storeys: 2,
+            shelter_class: ShelterClass::Urban()
+        }
+
+        Substance::Normal {
+            name: \"the substance\",
+            thermal_conductivity: 1.63,
+            specific_heat_capacity: 800,
+            density: 2400
+        }
+
+        Substance::Gas {
+            name: \"the gas\",
+            kind: StandardGas::Argon()
+        }
+
+        Material {
+            name: \"the material\",
+            substance: \"the substance\",
+            thickness: 0.013
+        }
+
+        Construction {
+            name: \"the construction\",
+            materials: [\"the material\", \"the material\"]
+        }
+
+        Space {
+            name: \"Bedroom\",
+            volume: 42.1,
+            building: \"Main campus\",
+            infiltration: Infiltration::DesignFlowRate(1, 0.1, -2, 0, 0.5)
+        }
+
+        Space {
+            name: \"Kitchen\",
+            infiltration: Infiltration::EffectiveAirLeakageArea(300)
+        }
+
+        Surface {
+            name: \"the surface\",
+            construction: \"the construction\",
+            front_boundary: Boundary::Space(\"Bedroom\"),
+            back_boundary: Boundary::Ground(),
+            front_receives_sun: false,
+            vertices: [
+                0, 0, 0,
+                1, 0, 0,
+                1, 1, 0,
+                0, 1, 0
+            ]
+        }
+
+        Fenestration {
+            name: \"the window\",
+            construction: \"the construction\",
+            operation_type: FenestrationPositions::Continuous(),
+            fenestration_type: FenestrationType::Window(),
+            back_boundary: Boundary::Space(\"Kitchen\"),
+            vertices: [0, 0, 0, 1, 0, 0, 1, 1, 0]
+        }
+
+        HVAC::IdealHeaterCooler {
+            name: \"the ideal\",
+            target_spaces: [\"Bedroom\", \"Kitchen\"],
+            max_heating_power: 1500
+        }
+
+        HVAC::ElectricHeater {
+            name: \"the heater\",
+            target_space: \"Kitchen\"
+        }
+
+        Luminaire {
+            name: \"the luminaire\",
+            max_power: 120.5,
+            target_space: \"Bedroom\"
+        }
+        ";
+
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        let written = model.to_spl();
+
+        let (read_back, _header) = SimpleScanner::new(written.as_bytes(), 1)
+            .parse_model()
+            .unwrap();
+        assert_eq!(written, read_back.to_spl());
+
+        assert_eq!(read_back.buildings.len(), 1);
+        assert_eq!(read_back.substances.len(), 2);
+        assert_eq!(read_back.materials.len(), 1);
+        assert_eq!(read_back.constructions.len(), 1);
+        assert_eq!(read_back.spaces.len(), 2);
+        assert_eq!(read_back.surfaces.len(), 1);
+        assert_eq!(read_back.fenestrations.len(), 1);
+        assert_eq!(read_back.hvacs.len(), 2);
+        assert_eq!(read_back.luminaires.len(), 1);
+
+        // References are resolved again
+        assert!(Rc::ptr_eq(
+            &read_back.constructions[0].materials[1],
+            &read_back.materials[0]
+        ));
+        if let Ok(Boundary::Space(s)) = read_back.surfaces[0].front_boundary() {
+            assert!(Rc::ptr_eq(s, &read_back.spaces[0]));
+        } else {
+            panic!("Expecting front boundary to be a Space")
+        }
+        assert!(matches!(
+            read_back.surfaces[0].back_boundary(),
+            Ok(Boundary::Ground)
+        ));
+        if let HVAC::IdealHeaterCooler(hvac) = &read_back.hvacs[0] {
+            assert_eq!(hvac.target_spaces.len(), 2);
+            assert!(Rc::ptr_eq(&hvac.target_spaces[1], &read_back.spaces[1]));
+        } else {
+            panic!("Expecting an IdealHeaterCooler")
+        }
+        assert!((read_back.surfaces[0].area() - model.surfaces[0].area()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_write_read_escaped_strings() {
+        // Enum fields written without commas are still accepted
+        let src = br#"
+        Space {
+            name: "The \"big\" room",
+            infiltration: Infiltration::DesignFlowRate(1 0.1 -2 0 0.5)
+        }
+
+        Luminaire {
+            name: "C:\\lights",
+            max_power: 120.5,
+            target_space: "The \"big\" room"
+        }
+        "#;
+
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.spaces[0].name(), "The \"big\" room");
+        assert_eq!(model.luminaires[0].name(), "C:\\lights");
+
+        let written = model.to_spl();
+        let (read_back, _header) = SimpleScanner::new(written.as_bytes(), 1)
+            .parse_model()
+            .unwrap();
+        assert_eq!(written, read_back.to_spl());
+        assert_eq!(read_back.spaces[0].name(), "The \"big\" room");
+        assert_eq!(read_back.luminaires[0].name(), "C:\\lights");
+        assert!(Rc::ptr_eq(
+            read_back.luminaires[0].target_space().unwrap(),
+            &read_back.spaces[0]
+        ));
+        assert!(matches!(
+            read_back.spaces[0].infiltration(),
+            Ok(crate::Infiltration::DesignFlowRate(..))
+        ));
+    }
+
+    #[test]
+    fn test_write_file() {
+        let (model, _state_header) =
+            SimpleModel::from_file("./test_data/box.spl".to_string()).unwrap();
+
+        let filename = std::env::temp_dir().join("simple_model_box_round_trip.spl");
+        let filename = filename.to_str().unwrap().to_string();
+        model.write_to(filename.clone()).unwrap();
+
+        let (read_back, _state_header) = SimpleModel::from_file(filename).unwrap();
+        assert_eq!(model.to_spl(), read_back.to_spl());
+    }
+
     use crate::substance::Normal;
 
     #[test]
@@ -0,0 +1,278 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::{Float, SimpleModelError};
+use ::schedule::Schedule as _;
+use calendar::Date;
+use derive::{ObjectAPI, ObjectIO};
+use std::rc::Rc;
+
+/// The clothing (in clo) of occupants without a `clothing_schedule`
+const DEFAULT_CLOTHING: Float = 1.0;
+
+/// The metabolic rate (in met) of occupants without a `metabolic_rate_schedule`
+const DEFAULT_ACTIVITY_LEVEL: Float = 1.0;
+
+/// A group of people that occupy a [`Space`].
+///
+/// The number of people is given either as a `count` or as a
+/// `density` (i.e., people per m2 of floor area of the space).
+///
+/// An `Occupant` is a homogeneous group: all of its members share the
+/// same clothing, activity level and location (i.e., a single set of
+/// personal state elements), so they cannot be separated during the
+/// simulation. People who behave differently need to be modelled as
+/// different `Occupant`s (e.g., with a `count` of 1).
+///
+/// ### Example
+///
+/// ```rs
+/// Occupant {
+///     name: "Office workers",
+///     space: "Office",
+///     density: 0.1,
+///     clothing_schedule: "Office clothing",
+///     metabolic_rate_schedule: "Office work"
+/// }
+/// ```
+#[derive(ObjectIO, ObjectAPI, Clone)]
+pub struct Occupant {
+    /// The name of the group of occupants
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The [`Space`] that these occupants belong to
+    pub space: Rc<Space>,
+
+    /// The number of people
+    count: Option<Float>,
+
+    /// The number of people per m2 of floor area of the `space`.
+    /// It is used only if no `count` is given.
+    density: Option<Float>,
+
+    /// The metabolic rate of the occupants (in met) over time
+    metabolic_rate_schedule: Option<Rc<Schedule>>,
+
+    /// The clothing of the occupants (in clo) over time
+    clothing_schedule: Option<Rc<Schedule>>,
+
+    /// The amount of clothing the occupants are using, in clo
+    #[personal]
+    clothing: StateElementField,
+
+    /// The metabolic rate of the occupants, in met
+    #[personal]
+    activity_level: StateElementField,
+
+    /// The index of the [`Space`] in which the occupants
+    /// are at the moment
+    #[personal]
+    location: StateElementField,
+}
+
+impl Occupant {
+    /// Gets the number of people in this group; either its `count` or its
+    /// `density` multiplied by the floor area of its `space`
+    pub fn number_of_people(&self, model: &SimpleModel) -> Result<Float, SimpleModelError> {
+        if let Ok(count) = self.count() {
+            return Ok(*count);
+        }
+        match self.density() {
+            Ok(density) => Ok(density * self.space.floor_area(model)),
+            Err(_) => Err(SimpleModelError::UnassignedField {
+                object_type: "Occupant".to_string(),
+                object_name: self.name.clone(),
+                field: "count".to_string(),
+            }),
+        }
+    }
+
+    /// Gets the clothing (in clo) of the occupants at a certain [`Date`],
+    /// from its `clothing_schedule` (or a default value, if there is none)
+    pub fn clothing_at(&self, date: Date) -> Float {
+        match self.clothing_schedule() {
+            Ok(schedule) => schedule.get(date),
+            Err(_) => DEFAULT_CLOTHING,
+        }
+    }
+
+    /// Gets the metabolic rate (in met) of the occupants at a certain
+    /// [`Date`], from its `metabolic_rate_schedule` (or a default value,
+    /// if there is none)
+    pub fn activity_level_at(&self, date: Date) -> Float {
+        match self.metabolic_rate_schedule() {
+            Ok(schedule) => schedule.get(date),
+            Err(_) => DEFAULT_ACTIVITY_LEVEL,
+        }
+    }
+
+    /// Pushes the personal states of the [`Occupant`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    /// These are shared by the whole group.
+    pub(crate) fn push_personal_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Occupant has not been added to the model");
+        let start = Date {
+            month: 1,
+            day: 1,
+            hour: 0.,
+        };
+
+        let state_index = state.push(
+            SimulationStateElement::Clothing(obj_index),
+            self.clothing_at(start),
+        );
+        self.set_clothing_index(state_index);
+
+        let state_index = state.push(
+            SimulationStateElement::ActivityLevel(obj_index),
+            self.activity_level_at(start),
+        );
+        self.set_activity_level_index(state_index);
+
+        let space_index = *self
+            .space
+            .index()
+            .expect("The Space of an Occupant must be added to the model before the Occupant")
+            as Float;
+        let state_index = state.push(
+            SimulationStateElement::OccupantLocation(obj_index),
+            space_index,
+        );
+        self.set_location_index(state_index);
+    }
+}
+
+impl SimpleModel {
+    /// Adds an [`Occupant`] to the [`SimpleModel`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `space` of the occupant is not in the model
+    pub fn add_occupant(
+        &mut self,
+        mut add: Occupant,
+        state: &mut SimulationStateHeader,
+    ) -> Rc<Occupant> {
+        // Check the index of this object
+        let obj_index = self.occupants.len();
+        add.set_index(obj_index);
+
+        // Push the personal states, and map into the object
+        add.push_personal_states(state);
+
+        // Add to model, and return a reference
+        let add = Rc::new(add);
+        self.occupants.push(Rc::clone(&add));
+        add
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_occupant() {
+        let src = b"
+Schedule {
+    name: \"always 0.5\",
+    profile: ScheduleProfile::Constant(0.5)
+}
+
+Space { name: \"kitchen\" }
+Space { name: \"bedroom\" }
+
+Occupant {
+    name: \"the family\",
+    space: \"bedroom\",
+    count: 4,
+    clothing_schedule: \"always 0.5\"
+}
+
+Occupant {
+    name: \"nobody\",
+    space: \"kitchen\"
+}
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::FixedClosed(),
+    fenestration_type: FenestrationType::Window(),
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1]
+}
+
+Substance::Normal { name: \"glass\" }
+
+Material {
+    name: \"the material\",
+    substance: \"glass\",
+    thickness: 0.004
+}
+
+Construction {
+    name: \"the construction\",
+    materials: [\"the material\"]
+}
+";
+        let (model, mut header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.occupants.len(), 2);
+
+        let family = &model.occupants[0];
+        assert!(Rc::ptr_eq(&family.space, &model.spaces[1]));
+        assert_eq!(family.number_of_people(&model).unwrap(), 4.);
+        assert!(family.clothing_schedule().is_ok());
+        assert!(model.occupants[1].number_of_people(&model).is_err());
+
+        // Personal elements go first
+        assert_eq!(header.n_individual(), 6);
+        assert_eq!(header.elements[0], SimulationStateElement::Clothing(0));
+        assert_eq!(header.elements[1], SimulationStateElement::ActivityLevel(0));
+        assert_eq!(
+            header.elements[2],
+            SimulationStateElement::OccupantLocation(0)
+        );
+
+        let mut state = header.take_values().unwrap();
+        assert_eq!(family.location(&state), Some(1.));
+        assert_eq!(model.occupants[1].location(&state), Some(0.));
+        // Initial values come from the schedules, if any
+        assert_eq!(family.clothing(&state), Some(0.5));
+        assert_eq!(family.activity_level(&state), Some(DEFAULT_ACTIVITY_LEVEL));
+        assert_eq!(model.occupants[1].clothing(&state), Some(DEFAULT_CLOTHING));
+        family.set_clothing(&mut state, 0.8);
+        assert_eq!(family.clothing(&state), Some(0.8));
+    }
+}
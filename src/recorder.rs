@@ -0,0 +1,343 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::model::SimpleModel;
+use crate::simulation_state::{SimulationState, SimulationStateHeader};
+use crate::simulation_state_element::SimulationStateElement;
+use crate::{Float, SimpleModelError};
+use calendar::Date;
+
+/// The period over which the values of a [`Recorder`]
+/// are aggregated
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AggregationPeriod {
+    /// One value per hour
+    Hourly,
+
+    /// One value per day
+    Daily,
+}
+
+/// How the values recorded within an [`AggregationPeriod`]
+/// become a single value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Aggregation {
+    /// The average of the values (e.g., for temperatures)
+    Mean,
+
+    /// The sum of the values (e.g., for energy)
+    Sum,
+}
+
+/// Records the values of some [`SimulationStateElement`]s over
+/// time, so they can be exported (e.g., as CSV) after the simulation.
+///
+/// The values are buffered in memory, one row per recorded
+/// timestep.
+#[derive(Clone)]
+pub struct Recorder {
+    /// The elements being recorded
+    elements: Vec<SimulationStateElement>,
+
+    /// The positions of the `elements` within the [`SimulationState`]
+    positions: Vec<usize>,
+
+    /// The date of each row
+    dates: Vec<Date>,
+
+    /// The recorded values, one row per timestep and
+    /// one column per element
+    values: Vec<Vec<Float>>,
+}
+
+impl Recorder {
+    /// Creates a new [`Recorder`] that will record some elements
+    /// of the states described by `header`.
+    ///
+    /// Returns an error if any of the `elements` is not in the `header`.
+    pub fn new(
+        header: &SimulationStateHeader,
+        elements: Vec<SimulationStateElement>,
+    ) -> Result<Self, SimpleModelError> {
+        let mut positions = Vec::with_capacity(elements.len());
+        for e in elements.iter() {
+            match header.find(e) {
+                Some(i) => positions.push(i),
+                None => {
+                    return Err(SimpleModelError::MissingStateElement {
+                        element: format!("{:?}", e),
+                    })
+                }
+            }
+        }
+        Ok(Self {
+            elements,
+            positions,
+            dates: Vec::new(),
+            values: Vec::new(),
+        })
+    }
+
+    /// Creates a new [`Recorder`] that will record all the physical
+    /// elements of the states described by `header`
+    pub fn all_physical(header: &SimulationStateHeader) -> Self {
+        let (positions, elements) = header.iter_physical().unzip();
+        Self {
+            elements,
+            positions,
+            dates: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Records the values of the elements in `state`, at a certain `date`
+    pub fn record(&mut self, date: Date, state: &SimulationState) {
+        self.dates.push(date);
+        self.values
+            .push(self.positions.iter().map(|i| state[*i]).collect());
+    }
+
+    /// Gets the elements being recorded (i.e., the columns)
+    pub fn elements(&self) -> &[SimulationStateElement] {
+        &self.elements
+    }
+
+    /// Gets the date of each recorded timestep (i.e., the rows)
+    pub fn dates(&self) -> &[Date] {
+        &self.dates
+    }
+
+    /// Gets the recorded values, one row per timestep
+    pub fn values(&self) -> &[Vec<Float>] {
+        &self.values
+    }
+
+    /// Gets the recorded values of a single element, if it is being recorded
+    pub fn column(&self, e: &SimulationStateElement) -> Option<Vec<Float>> {
+        let j = self.elements.iter().position(|x| x == e)?;
+        Some(self.values.iter().map(|row| row[j]).collect())
+    }
+
+    /// Returns the number of recorded timesteps
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks whether no timestep has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns a new [`Recorder`] that keeps only one of every `n`
+    /// recorded timesteps (starting with the first one)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero
+    pub fn downsample(&self, n: usize) -> Self {
+        assert!(n > 0, "Cannot downsample a Recorder by 0");
+        Self {
+            elements: self.elements.clone(),
+            positions: self.positions.clone(),
+            dates: self.dates.iter().step_by(n).copied().collect(),
+            values: self.values.iter().step_by(n).cloned().collect(),
+        }
+    }
+
+    /// Returns a new [`Recorder`] with one row per `period`, whose values
+    /// are the `aggregation` of all the timesteps recorded within that period.
+    ///
+    /// Consecutive timesteps belong to the same period if they have the
+    /// same month and day (and, when `Hourly`, the same whole hour). The date
+    /// of each row is the beginning of its period.
+    pub fn aggregate(&self, period: AggregationPeriod, aggregation: Aggregation) -> Self {
+        let start_of_period = |date: &Date| {
+            let mut ret = *date;
+            ret.hour = match period {
+                AggregationPeriod::Hourly => date.hour.floor(),
+                AggregationPeriod::Daily => 0.,
+            };
+            ret
+        };
+
+        let mut dates: Vec<Date> = Vec::new();
+        let mut values: Vec<Vec<Float>> = Vec::new();
+        let mut counts: Vec<usize> = Vec::new();
+        for (date, row) in self.dates.iter().zip(self.values.iter()) {
+            let date = start_of_period(date);
+            match (dates.last(), values.last_mut(), counts.last_mut()) {
+                (Some(last), Some(acc), Some(n))
+                    if (last.month, last.day, last.hour) == (date.month, date.day, date.hour) =>
+                {
+                    acc.iter_mut().zip(row.iter()).for_each(|(a, v)| *a += v);
+                    *n += 1;
+                }
+                _ => {
+                    dates.push(date);
+                    values.push(row.clone());
+                    counts.push(1);
+                }
+            }
+        }
+
+        if aggregation == Aggregation::Mean {
+            for (row, n) in values.iter_mut().zip(counts.iter()) {
+                row.iter_mut().for_each(|v| *v /= *n as Float);
+            }
+        }
+
+        Self {
+            elements: self.elements.clone(),
+            positions: self.positions.clone(),
+            dates,
+            values,
+        }
+    }
+
+    /// Writes the recorded values as CSV. The first row contains the
+    /// names of the columns: `Month`, `Day` and `Hour`, followed by a
+    /// label for each element built from the names of the objects in
+    /// the `model` (e.g., `Bedroom: dry bulb temperature`).
+    pub fn to_csv(&self, model: &SimpleModel) -> String {
+        let escape = |s: String| {
+            if s.contains(',') || s.contains('"') {
+                format!("\"{}\"", s.replace('"', "\"\""))
+            } else {
+                s
+            }
+        };
+
+        let mut ret = "Month,Day,Hour".to_string();
+        for e in self.elements.iter() {
+            ret.push(',');
+            ret.push_str(&escape(e.label(model)));
+        }
+        ret.push('\n');
+
+        for (date, row) in self.dates.iter().zip(self.values.iter()) {
+            ret.push_str(&format!("{},{},{}", date.month, date.day, date.hour));
+            for v in row.iter() {
+                ret.push_str(&format!(",{}", v));
+            }
+            ret.push('\n');
+        }
+        ret
+    }
+
+    /// Writes the recorded values into a CSV file (see [`Recorder::to_csv`])
+    pub fn write_csv(&self, model: &SimpleModel, filename: String) -> Result<(), SimpleModelError> {
+        match std::fs::write(filename.clone(), self.to_csv(model)) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(SimpleModelError::IO {
+                filename,
+                message: format!("Could not write CSV file: {}", e),
+            }),
+        }
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_record_and_export() {
+        let src = b"
+Space { name: \"Bedroom\" }
+Space { name: \"Kitchen, and dining\" }
+";
+        let (model, _) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let mut header = model.build_state_header();
+
+        // Unknown elements are rejected
+        assert!(Recorder::new(
+            &header,
+            vec![SimulationStateElement::SpaceDryBulbTemperature(9)]
+        )
+        .is_err());
+
+        let mut recorder = Recorder::new(
+            &header,
+            vec![
+                SimulationStateElement::SpaceDryBulbTemperature(0),
+                SimulationStateElement::SpaceDryBulbTemperature(1),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            Recorder::all_physical(&header).elements().len(),
+            header.iter_physical().count()
+        );
+
+        // Record two days, every half hour
+        let mut state = header.take_values().unwrap();
+        let bedroom = model.spaces[0].dry_bulb_temperature_index().unwrap();
+        let kitchen = model.spaces[1].dry_bulb_temperature_index().unwrap();
+        for step in 0..96 {
+            let hour = (step % 48) as Float / 2.;
+            state[bedroom] = hour;
+            state[kitchen] = 1.;
+            let date = Date {
+                month: 1,
+                day: 1 + step / 48,
+                hour,
+            };
+            recorder.record(date, &state);
+        }
+        assert_eq!(recorder.len(), 96);
+        assert_eq!(
+            recorder
+                .column(&SimulationStateElement::SpaceDryBulbTemperature(1))
+                .unwrap(),
+            vec![1.; 96]
+        );
+
+        // Downsample
+        let hourly = recorder.downsample(2);
+        assert_eq!(hourly.len(), 48);
+        assert_eq!(hourly.dates()[1].hour, 1.);
+
+        // Aggregate
+        let hourly = recorder.aggregate(AggregationPeriod::Hourly, Aggregation::Mean);
+        assert_eq!(hourly.len(), 48);
+        assert_eq!(hourly.values()[3], vec![3.25, 1.]);
+        let daily = recorder.aggregate(AggregationPeriod::Daily, Aggregation::Sum);
+        assert_eq!(daily.len(), 2);
+        assert_eq!(daily.dates()[1].day, 2);
+        assert_eq!(daily.values()[1], vec![564., 48.]);
+
+        // Export
+        let csv = daily.to_csv(&model);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "Month,Day,Hour,Bedroom: dry bulb temperature,\"Kitchen, and dining: dry bulb temperature\""
+        );
+        assert_eq!(lines.next().unwrap(), "1,1,0,564,48");
+        assert_eq!(lines.next().unwrap(), "1,2,0,564,48");
+        assert!(lines.next().is_none());
+    }
+}
@@ -19,8 +19,9 @@ SOFTWARE.
 */
 
 use crate::{
-    hvac::{ElectricHeater, IdealHeaterCooler, HVAC},
-    Fenestration, Luminaire, SimpleModel, SimulationState, Space, Surface,
+    hvac::{AirSourceHeatPump, ElectricHeater, FanCoil, GasBoiler, IdealHeaterCooler, HVAC},
+    AirExchange, Equipment, Fenestration, Luminaire, Occupant, ShadingDevice, SimpleModel,
+    SimulationState, Space, Surface, Thermostat, Ventilation,
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -36,8 +37,200 @@ pub fn register_control_api(
     Surface::register_api(engine, model, state, research_mode);
     Fenestration::register_api(engine, model, state, research_mode);
     Luminaire::register_api(engine, model, state, research_mode);
+    Equipment::register_api(engine, model, state, research_mode);
+    Thermostat::register_api(engine, model, state, research_mode);
+    Ventilation::register_api(engine, model, state, research_mode);
+    AirExchange::register_api(engine, model, state, research_mode);
+    Occupant::register_api(engine, model, state, research_mode);
+    ShadingDevice::register_api(engine, model, state, research_mode);
 
     HVAC::register_api(engine, model, state, research_mode);
     ElectricHeater::register_api(engine, model, state, research_mode);
     IdealHeaterCooler::register_api(engine, model, state, research_mode);
+    AirSourceHeatPump::register_api(engine, model, state, research_mode);
+    GasBoiler::register_api(engine, model, state, research_mode);
+    FanCoil::register_api(engine, model, state, research_mode);
+
+    register_space_queries(engine, model);
+}
+
+/// Registers the properties that let scripts find the objects
+/// related to a [`Space`] (e.g., `space("kitchen").luminaires`)
+fn register_space_queries(engine: &mut rhai::Engine, model: &Rc<SimpleModel>) {
+    let new_mod = Rc::clone(model);
+    engine.register_get("fenestrations", move |this: &mut Rc<Space>| {
+        this.bounding_fenestrations(&new_mod)
+            .into_iter()
+            .map(rhai::Dynamic::from)
+            .collect::<rhai::Array>()
+    });
+
+    let new_mod = Rc::clone(model);
+    engine.register_get("hvacs", move |this: &mut Rc<Space>| {
+        this.hvacs(&new_mod)
+            .iter()
+            .map(|h| h.to_dynamic())
+            .collect::<rhai::Array>()
+    });
+
+    let new_mod = Rc::clone(model);
+    engine.register_get("luminaires", move |this: &mut Rc<Space>| {
+        this.luminaires(&new_mod)
+            .into_iter()
+            .map(rhai::Dynamic::from)
+            .collect::<rhai::Array>()
+    });
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_collections() {
+        let src = b"
+Space { name: \"Kitchen\" }
+Space { name: \"Bedroom\" }
+
+Substance::Normal { name: \"the substance\" }
+Material { name: \"the material\", substance: \"the substance\", thickness: 0.1 }
+Construction { name: \"the construction\", materials: [\"the material\"] }
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::FixedClosed(),
+    fenestration_type: FenestrationType::Window(),
+    back_boundary: Boundary::Space(\"Bedroom\"),
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1]
+}
+
+Luminaire { name: \"Kitchen lamp\", target_space: \"Kitchen\" }
+Luminaire { name: \"Bedroom lamp\", target_space: \"Bedroom\" }
+HVAC::ElectricHeater { name: \"heater\", target_space: \"Bedroom\" }
+HVAC::IdealHeaterCooler { name: \"ideal\", target_spaces: [\"Kitchen\", \"Bedroom\"] }
+";
+        let (model, mut header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let state = header.take_values().unwrap();
+
+        let model = Rc::new(model);
+        let state = Rc::new(RefCell::new(state));
+        let mut engine = rhai::Engine::new();
+        register_control_api(&mut engine, &model, &state, true);
+
+        let n: rhai::INT = engine
+            .eval("n_spaces() + n_luminaires() + n_hvacs() + n_fenestrations()")
+            .unwrap();
+        assert_eq!(n, 7);
+
+        // Loop over collections
+        let counts: String = engine
+            .eval(
+                "
+            let counts = \"\";
+            for s in spaces() {
+                counts += `${s.luminaires.len()},${s.hvacs.len()},${s.fenestrations.len()};`;
+            }
+            counts
+        ",
+            )
+            .unwrap();
+        assert_eq!(counts, "1,1,0;1,2,1;");
+
+        // HVACs keep their own type
+        let n: rhai::INT = engine
+            .eval(
+                "
+            let n = 0;
+            for h in space(\"Bedroom\").hvacs {
+                h.power_consumption = 10.0;
+                n += 1;
+            }
+            n + hvacs().len() + surfaces().len()
+        ",
+            )
+            .unwrap();
+        assert_eq!(n, 4);
+        if let HVAC::ElectricHeater(heater) = &model.hvacs[0] {
+            assert_eq!(
+                heater.heating_cooling_consumption(&state.borrow()),
+                Some(10.)
+            );
+        } else {
+            panic!("Expecting an ElectricHeater")
+        }
+    }
+
+    #[test]
+    fn test_static_properties() {
+        let src = b"
+Space { name: \"Bedroom\", volume: 30.0 }
+
+Substance::Normal { name: \"the substance\" }
+Material { name: \"the material\", substance: \"the substance\", thickness: 0.1 }
+Construction { name: \"the construction\", materials: [\"the material\"] }
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::FixedClosed(),
+    fenestration_type: FenestrationType::Window(),
+    back_boundary: Boundary::Space(\"Bedroom\"),
+    vertices: [0, 0, 0, 2, 0, 0, 2, 0, 1, 0, 0, 1]
+}
+
+Luminaire { name: \"the lamp\", target_space: \"Bedroom\", max_power: 60.0 }
+HVAC::ElectricHeater { name: \"heater\", target_space: \"Bedroom\", max_heating_power: 1500.0 }
+";
+        let (model, mut header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let state = header.take_values().unwrap();
+
+        let model = Rc::new(model);
+        let state = Rc::new(RefCell::new(state));
+        let mut engine = rhai::Engine::new();
+        register_control_api(&mut engine, &model, &state, true);
+
+        // Plain fields
+        let v: crate::Float = engine
+            .eval(
+                "
+            let space = space(0);
+            space.volume + luminaire(0).max_power + hvac(0).max_heating_power
+        ",
+            )
+            .unwrap();
+        assert!((v - 1590.).abs() < 1e-9);
+        let s: String = engine
+            .eval(
+                "
+            let window = fenestration(0);
+            `${space(0).name}|${window.operation_type}|${window.construction}|${hvac(0).target_space.name}`
+        ",
+            )
+            .unwrap();
+        assert_eq!(
+            s,
+            "Bedroom|FenestrationPositions::FixedClosed()|the construction|Bedroom"
+        );
+
+        // Computed
+        let area: crate::Float = engine.eval("fenestration(0).area").unwrap();
+        assert!((area - 2.).abs() < 1e-9);
+        let operable: bool = engine.eval("fenestration(0).is_operable").unwrap();
+        assert!(!operable);
+
+        // Read-only and unassigned
+        assert!(engine
+            .eval::<crate::Float>("let s = space(0); s.volume = 2.0; s.volume")
+            .is_err());
+        assert!(engine.eval::<crate::Float>("hvac(0).availability").is_err());
+    }
 }
@@ -0,0 +1,621 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+use crate::model::SimpleModel;
+use crate::scanner::tokens::{Token, TokenType};
+use crate::scanner::SimpleScanner;
+use crate::simulation_state::SimulationStateHeader;
+use crate::SimpleModelError;
+
+/// A Rhai script found in a ` ```rust control ` block of
+/// a Markdown model file
+#[derive(Debug, Clone)]
+pub struct ControlScript {
+    /// The line of the Markdown file in which
+    /// the script starts (i.e., the one after the
+    /// opening of the block)
+    pub line: usize,
+
+    /// The source code of the script
+    pub source: String,
+}
+
+/// The kinds of fenced blocks that can be found in a
+/// Markdown model file
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum BlockKind {
+    /// A block containing SIMPLE model objects (i.e., ` ```rust `)
+    Model,
+
+    /// A block containing a control script (i.e., ` ```rust control `)
+    Control,
+
+    /// Any other fenced block (e.g., ` ```bash `), which is just text
+    Other,
+}
+
+/// A scanner for literate Markdown model files.
+///
+/// These files contain prose, plus fenced ` ```rust ` blocks with SIMPLE
+/// model objects and fenced ` ```rust control ` blocks with Rhai control
+/// scripts. Everything outside of those blocks is ignored.
+pub struct MDScanner<'a> {
+    pub line: usize,
+
+    source: &'a [u8],
+
+    current_index: usize,
+
+    start_index: usize,
+
+    finished: bool,
+}
+
+impl<'a> MDScanner<'a> {
+    /// Creates a new [`MDScanner`]
+    pub fn new(source: &'a [u8]) -> Self {
+        Self {
+            finished: source.is_empty(),
+            source,
+            line: 1,
+            current_index: 0,
+            start_index: 0,
+        }
+    }
+
+    /// Gets the column (starting at 1) of the `index`-th byte of the `source`.
+    fn column(&self, index: usize) -> usize {
+        let index = index.min(self.source.len());
+        match self.source[..index].iter().rposition(|c| *c == b'\n') {
+            Some(newline) => index - newline,
+            None => index + 1,
+        }
+    }
+
+    /// Builds a [`Token`] corresponding to the current position of
+    /// the scanner (e.g., `start_index` and `current_index`, `line`,
+    /// etc.)
+    fn make_token(&self, token_type: TokenType) -> Token<'a> {
+        let txt = &self.source[self.start_index..self.current_index];
+        Token {
+            token_type,
+            txt,
+            line: self.line,
+            column: self.column(self.start_index),
+            length: self.current_index - self.start_index,
+            start: self.start_index,
+        }
+    }
+
+    /// Checks whether the `char` at the `current_index` in the `source`
+    /// is equal to the `expected` char. If it is, it returns `true` and
+    /// advances one step. If not, it returns `false` and does not
+    /// advance.
+    fn match_char(&mut self, expected: char) -> bool {
+        if self.finished || self.source[self.current_index] as char != expected {
+            return false;
+        }
+        self.advance();
+        true
+    }
+
+    /// Advances one `char` in the `source`, returning the consumed
+    /// `char` inside of an `Option`. If finished, it will mark the
+    /// [`MDScanner`] as finished and return `None`
+    fn advance(&mut self) -> Option<char> {
+        if let Some(v) = self.source.get(self.current_index) {
+            self.current_index += 1;
+            if self.current_index == self.source.len() {
+                self.finished = true;
+            }
+            Some(*v as char)
+        } else {
+            self.finished = true;
+            None
+        }
+    }
+
+    /// Gets the `char` at the `current_index`. Returns `\0` if
+    /// finished.
+    fn peek(&self) -> char {
+        if self.finished {
+            return '\0';
+        }
+        self.source[self.current_index] as char
+    }
+
+    /// Gets the `char` following the `current_index`. Returns `\0` if
+    /// finished.
+    fn peek_next(&self) -> char {
+        if self.finished || self.current_index + 1 == self.source.len() {
+            return '\0';
+        }
+        self.source[self.current_index + 1] as char
+    }
+
+    /* MD SCANNER AND SIMPLESCANNER ARE THE SAME UP TO HERE */
+
+    /// Skips the white spaces, counting the lines
+    fn skip_white_space(&mut self) {
+        loop {
+            match self.peek() {
+                ' ' | '\r' | '\t' => {
+                    self.advance();
+                }
+                '\n' => {
+                    self.line += 1;
+                    self.advance();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Checks whether there is nothing but white space between
+    /// the beginning of the line and the `start_index`
+    fn at_line_start(&self) -> bool {
+        self.source[..self.start_index]
+            .iter()
+            .rev()
+            .take_while(|c| **c != b'\n')
+            .all(|c| *c == b' ' || *c == b'\t')
+    }
+
+    /// Consumes a Markdown word (i.e., anything until the next
+    /// white space or inline code)
+    fn word(&mut self) -> Token<'a> {
+        while !self.finished && !self.peek().is_ascii_whitespace() && self.peek() != '`' {
+            self.advance();
+        }
+        self.make_token(TokenType::Word)
+    }
+
+    /// Consumes the info string of a fence (e.g., `rust control`) and
+    /// returns the [`Token`] that opens or closes a code block
+    fn fence(&mut self) -> Token<'a> {
+        while !self.finished && self.peek() != '\n' {
+            self.advance();
+        }
+        let mut ret = self.make_token(TokenType::SimpleBuildingBlockLimit);
+        if Self::block_kind(ret.txt) == BlockKind::Control {
+            ret.token_type = TokenType::ControlBlockLimit;
+        }
+        ret
+    }
+
+    /// Scans a `Token` in `Markdown` read mode
+    pub fn scan_token(&mut self) -> Token<'a> {
+        self.skip_white_space();
+        self.start_index = self.current_index;
+
+        let c = match self.advance() {
+            Some(v) => v,
+            None => return self.make_token(TokenType::EOF),
+        };
+
+        match c {
+            '#' => self.make_token(TokenType::Hash),
+            '_' => self.make_token(TokenType::Underscore),
+            '!' => self.make_token(TokenType::Bang),
+            '*' => {
+                if self.match_char('*') {
+                    self.make_token(TokenType::StarStar)
+                } else {
+                    self.make_token(TokenType::Star)
+                }
+            }
+            '=' if self.peek() == '=' && self.peek_next() == '=' => {
+                self.advance();
+                self.advance();
+                self.make_token(TokenType::HorizontalRule)
+            }
+            '`' => {
+                // Code blocks are only opened and closed at the
+                // beginning of a line
+                if self.peek() == '`' && self.peek_next() == '`' && self.at_line_start() {
+                    self.advance();
+                    self.advance();
+                    self.fence()
+                } else {
+                    self.make_token(TokenType::CodeBoundary)
+                }
+            }
+            _ => self.word(),
+        }
+    }
+
+    /// Identifies the kind of block opened by a fence, based on
+    /// its info string (e.g., ` ```rust control `)
+    fn block_kind(fence: &[u8]) -> BlockKind {
+        let info = String::from_utf8_lossy(&fence[3..]);
+        let mut words = info.split_whitespace();
+        match words.next() {
+            Some("rust") | Some("rs") => match words.next() {
+                Some("control") => BlockKind::Control,
+                _ => BlockKind::Model,
+            },
+            _ => BlockKind::Other,
+        }
+    }
+
+    /// Gets the index at which the line containing `index` starts
+    fn line_start(&self, index: usize) -> usize {
+        match self.source[..index].iter().rposition(|c| *c == b'\n') {
+            Some(newline) => newline + 1,
+            None => 0,
+        }
+    }
+
+    /// Consumes the contents of a model block—using the tokens of the
+    /// [`SimpleScanner`], so that strings and comments are respected—and
+    /// returns the index at which the closing fence starts.
+    fn skip_model_block(&mut self, opened_at: usize) -> Result<usize, SimpleModelError> {
+        let content_start = self.current_index;
+        let mut scanner = SimpleScanner::new(&self.source[content_start..], self.line);
+        loop {
+            let token = scanner.scan_token();
+            match token.token_type {
+                TokenType::SimpleBuildingBlockLimit => {
+                    // The token starts right after the backticks
+                    let end = content_start + token.start;
+                    self.current_index = end;
+                    self.finished = end == self.source.len();
+                    self.line = scanner.line;
+                    // Consume the closing fence
+                    self.start_index = end - 3;
+                    self.fence();
+                    return Ok(self.line_start(end - 3));
+                }
+                TokenType::EOF => {
+                    return Err(SimpleModelError::Syntax {
+                        line: opened_at,
+                        column: 1,
+                        message: "Code block is never closed".to_string(),
+                    })
+                }
+                TokenType::Error => {
+                    return Err(SimpleModelError::Syntax {
+                        line: token.line,
+                        column: token.column,
+                        message: scanner.error_msg,
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Consumes the contents of any other block, returning the index
+    /// at which the closing fence starts.
+    fn skip_text_block(&mut self, opened_at: usize) -> Result<usize, SimpleModelError> {
+        loop {
+            let token = self.scan_token();
+            match token.token_type {
+                TokenType::SimpleBuildingBlockLimit | TokenType::ControlBlockLimit => {
+                    return Ok(self.line_start(token.start))
+                }
+                TokenType::EOF => {
+                    return Err(SimpleModelError::Syntax {
+                        line: opened_at,
+                        column: 1,
+                        message: "Code block is never closed".to_string(),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Splits the Markdown source into model and control blocks.
+    ///
+    /// The model source is returned with the same number of lines
+    /// as the original file—everything outside of model blocks is
+    /// replaced by empty lines—so that errors reported by the
+    /// [`SimpleScanner`] point to lines in the Markdown file.
+    pub fn get_blocks(&mut self) -> Result<(Vec<u8>, Vec<ControlScript>), SimpleModelError> {
+        let mut model_source: Vec<u8> = Vec::with_capacity(self.source.len());
+        let mut scripts: Vec<ControlScript> = Vec::new();
+
+        // Where the text we have not copied into the model source starts
+        let mut copied_up_to = 0;
+
+        loop {
+            let token = self.scan_token();
+            let opened_at = token.line;
+            match token.token_type {
+                TokenType::EOF => break,
+                TokenType::SimpleBuildingBlockLimit | TokenType::ControlBlockLimit => {
+                    // The contents start in the line after the fence
+                    if self.match_char('\n') {
+                        self.line += 1;
+                    }
+                    let content_start = self.current_index;
+                    match Self::block_kind(token.txt) {
+                        BlockKind::Model => {
+                            let content_end = self.skip_model_block(opened_at)?;
+                            let skipped = &self.source[copied_up_to..content_start];
+                            model_source.extend(skipped.iter().filter(|c| **c == b'\n'));
+                            model_source
+                                .extend_from_slice(&self.source[content_start..content_end]);
+                            copied_up_to = content_end;
+                        }
+                        BlockKind::Control => {
+                            let content_end = self.skip_text_block(opened_at)?;
+                            let source =
+                                std::str::from_utf8(&self.source[content_start..content_end])
+                                    .map_err(|e| SimpleModelError::Syntax {
+                                        line: opened_at + 1,
+                                        column: e.valid_up_to() + 1,
+                                        message: "Markdown files must be UTF-8 encoded".to_string(),
+                                    })?;
+                            scripts.push(ControlScript {
+                                line: opened_at + 1,
+                                source: source.to_string(),
+                            });
+                        }
+                        BlockKind::Other => {
+                            self.skip_text_block(opened_at)?;
+                        }
+                    }
+                }
+                _ => { /* Just text */ }
+            }
+        }
+        let skipped = &self.source[copied_up_to..];
+        model_source.extend(skipped.iter().filter(|c| **c == b'\n'));
+
+        Ok((model_source, scripts))
+    }
+
+    /// Parses the model blocks into a [`SimpleModel`] and collects the
+    /// control scripts. Just like [`SimpleScanner::parse_model`], this
+    /// returns all the diagnostics if any object cannot be read (or if
+    /// there are `use` statements, which are not supported yet).
+    pub fn parse_model(
+        &mut self,
+    ) -> Result<(SimpleModel, SimulationStateHeader, Vec<ControlScript>), Vec<SimpleModelError>>
+    {
+        let (model, header, scripts, diagnostics) =
+            self.parse_model_lenient().map_err(|e| vec![e])?;
+        if diagnostics.is_empty() {
+            Ok((model, header, scripts))
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// Parses the model blocks in lenient mode (see
+    /// [`SimpleScanner::parse_model_lenient`]) and collects the control
+    /// scripts. This only fails if the Markdown blocks themselves cannot
+    /// be read (e.g., a code block that is never closed).
+    pub fn parse_model_lenient(
+        &mut self,
+    ) -> Result<
+        (
+            SimpleModel,
+            SimulationStateHeader,
+            Vec<ControlScript>,
+            Vec<SimpleModelError>,
+        ),
+        SimpleModelError,
+    > {
+        let (model_source, scripts) = self.get_blocks()?;
+        let mut scanner = SimpleScanner::new(&model_source, 1);
+        let (model, header, diagnostics) = scanner.parse_model_lenient();
+        Ok((model, header, scripts, diagnostics))
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_scan_token() {
+        let src = b"# Title **bold** `code` not ```a fence
+```rust control
+===";
+        let mut scanner = MDScanner::new(src);
+        let exp = [
+            TokenType::Hash,
+            TokenType::Word,
+            TokenType::StarStar,
+            TokenType::Word,
+            TokenType::CodeBoundary,
+            TokenType::Word,
+            TokenType::CodeBoundary,
+            TokenType::Word,
+            TokenType::CodeBoundary,
+            TokenType::CodeBoundary,
+            TokenType::CodeBoundary,
+            TokenType::Word,
+            TokenType::Word,
+            TokenType::ControlBlockLimit,
+            TokenType::HorizontalRule,
+            TokenType::EOF,
+        ];
+        for (i, exp) in exp.iter().enumerate() {
+            let token = scanner.scan_token();
+            assert_eq!(token.token_type, *exp, "token {}", i);
+        }
+        assert_eq!(scanner.line, 3);
+    }
+
+    #[test]
+    fn test_get_blocks() {
+        let src = b"# A title
+
+Some text with `inline code`.
+
+```rust
+Space {
+    name: \"Bedroom\"
+}
+```
+
+```bash
+echo \"Space { name: 'Not a space' }\"
+```
+
+```rust control
+let t = space(\"Bedroom\").dry_bulb_temperature;
+print(t);
+```
+";
+        let (model_source, scripts) = MDScanner::new(src).get_blocks().unwrap();
+
+        // Same number of lines
+        let model_str = std::str::from_utf8(&model_source).unwrap();
+        assert_eq!(
+            model_str.lines().count(),
+            std::str::from_utf8(src).unwrap().lines().count()
+        );
+        let lines: Vec<&str> = model_str.lines().collect();
+        assert_eq!(lines[5], "Space {");
+        assert!(!model_str.contains("Not a space"));
+        assert!(!model_str.contains("print"));
+
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].line, 16);
+        assert_eq!(
+            scripts[0].source,
+            "let t = space(\"Bedroom\").dry_bulb_temperature;\nprint(t);\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_model() {
+        let src = b"The spaces
+
+```rust
+Space {
+    name: \"Bedroom\",
+    volume: 42
+}
+```
+
+Some more text
+
+```rs
+Space {
+    name: \"Kitchen\"
+}
+```
+";
+        let (model, _header, scripts) = MDScanner::new(src).parse_model().unwrap();
+        assert_eq!(model.spaces.len(), 2);
+        assert_eq!(model.spaces[0].name, "Bedroom");
+        assert_eq!(model.spaces[1].name, "Kitchen");
+        assert!(scripts.is_empty());
+    }
+
+    #[test]
+    fn test_error_lines() {
+        let src = b"Some text
+
+```rust
+Space {
+    name: \"Bedroom\"
+}
+} // This is line 7
+```
+";
+        let err = match MDScanner::new(src).parse_model() {
+            Ok(_) => panic!("Expecting an error"),
+            Err(e) => e,
+        };
+        assert_eq!(err.len(), 1);
+        assert_eq!(err[0].position(), Some((7, 1)), "{}", err[0]);
+
+        // Unclosed block
+        let src = b"Some text
+
+```rust
+Space {
+    name: \"Bedroom\"
+}
+";
+        let err = MDScanner::new(src).get_blocks().unwrap_err();
+        assert_eq!(err.line(), Some(3), "{}", err);
+    }
+
+    #[test]
+    fn test_example_building() {
+        let src = std::fs::read("./test_data/example_building.md").unwrap();
+        let (model, _header, scripts, diagnostics) =
+            MDScanner::new(&src).parse_model_lenient().unwrap();
+
+        // The 'use' statements are reported, but do not stop the parsing
+        assert_eq!(diagnostics.len(), 2);
+        for (d, (line, statement)) in diagnostics.iter().zip([
+            (
+                62,
+                "simple_defaults::default_reflectances::wall as \"wall exterior\"",
+            ),
+            (65, "spectral_db::white_paint as \"white paint\""),
+        ]) {
+            match d {
+                SimpleModelError::UnsupportedUse {
+                    line: l,
+                    column,
+                    statement: s,
+                } => {
+                    assert_eq!(*l, line);
+                    assert_eq!(*column, 1);
+                    assert_eq!(s, statement);
+                }
+                _ => panic!("Expected an UnsupportedUse error, found {:?}", d),
+            }
+        }
+        match MDScanner::new(&src).parse_model() {
+            Err(errors) => assert_eq!(errors, diagnostics),
+            Ok(_) => panic!("Expected the 'use' statements to be reported"),
+        }
+
+        assert_eq!(scripts.len(), 1);
+        assert_eq!(scripts[0].line, 149);
+        assert!(scripts[0].source.starts_with("\nlet space_i = 0;"));
+
+        assert_eq!(model.substances.len(), 2);
+        assert_eq!(model.materials.len(), 2);
+        assert_eq!(model.constructions.len(), 2);
+        assert_eq!(model.buildings.len(), 1);
+        assert_eq!(model.spaces.len(), 1);
+        assert_eq!(model.surfaces.len(), 1);
+        assert_eq!(model.fenestrations.len(), 1);
+
+        assert_eq!(model.materials[0].name, "13cm concrete");
+        assert_eq!(model.materials[0].substance.name(), "concrete");
+        assert!(Rc::ptr_eq(
+            &model.constructions[1].materials[0],
+            &model.materials[1]
+        ));
+        assert!((model.surfaces[0].area() - 12.).abs() < 1e-5);
+        assert_eq!(model.spaces[0].name, "the space");
+        assert!(Rc::ptr_eq(
+            model.spaces[0].building().unwrap(),
+            &model.buildings[0]
+        ));
+    }
+}
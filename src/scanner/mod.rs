@@ -18,8 +18,10 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+pub mod md_scanner;
 pub mod simple_scanner;
 mod tokens;
 
-pub use simple_scanner::{make_error_msg, SimpleScanner};
-pub use tokens::TokenType;
+pub use md_scanner::{ControlScript, MDScanner};
+pub use simple_scanner::SimpleScanner;
+pub use tokens::{quote_string, TokenType};
@@ -20,21 +20,26 @@ SOFTWARE.
 use crate::model::SimpleModel;
 use crate::simulation_state::SimulationStateHeader;
 
+use crate::air_exchange::AirExchange;
 use crate::building::Building;
 use crate::construction::Construction;
+use crate::equipment::Equipment;
 use crate::fenestration::Fenestration;
 use crate::hvac::HVAC;
 use crate::luminaire::Luminaire;
 use crate::material::Material;
+use crate::occupant::Occupant;
+use crate::schedule::Schedule;
+use crate::shading::ShadingDevice;
 use crate::space::Space;
 use crate::substance::Substance;
 use crate::surface::Surface;
+use crate::thermostat::Thermostat;
+use crate::ventilation::Ventilation;
 
 use crate::scanner::tokens::*;
-
-pub fn make_error_msg(msg: String, ln: usize) -> String {
-    format!("Error [in line {}]: {}", ln, msg)
-}
+use crate::SimpleModelError;
+use std::collections::{HashMap, VecDeque};
 
 pub struct SimpleScanner<'a> {
     pub line: usize,
@@ -71,6 +76,33 @@ impl<'a> SimpleScanner<'a> {
         self.start_index = self.current_index;
     }
 
+    /// Gets the column (starting at 1) of the `index`-th byte of the `source`.
+    ///
+    /// If the `source` starts in the middle of a line (e.g., it is the slice
+    /// containing a single object), the columns of its first line are counted
+    /// from the beginning of the `source`.
+    pub fn column(&self, index: usize) -> usize {
+        let index = index.min(self.source.len());
+        match self.source[..index].iter().rposition(|c| *c == b'\n') {
+            Some(newline) => index - newline,
+            None => index + 1,
+        }
+    }
+
+    /// Gets the column (starting at 1) of the `current_index`
+    pub fn current_column(&self) -> usize {
+        self.column(self.current_index)
+    }
+
+    /// Builds a [`SimpleModelError::Syntax`] at the current position
+    pub fn syntax_error(&self, message: String) -> SimpleModelError {
+        SimpleModelError::Syntax {
+            line: self.line,
+            column: self.current_column(),
+            message,
+        }
+    }
+
     /// Builds a [`Token`] corresponding to the current position of
     /// the scanner (e.g., `start_index` and `current_index`, `line`,
     /// etc.)
@@ -80,6 +112,7 @@ impl<'a> SimpleScanner<'a> {
             token_type,
             txt,
             line: self.line,
+            column: self.column(self.start_index),
             length: self.current_index - self.start_index,
             start: self.start_index,
         }
@@ -167,20 +200,22 @@ impl<'a> SimpleScanner<'a> {
         // Token will have this line reported
         let start_line = self.line;
 
-        let mut next = self.peek();
-
-        // Advance as much as possible
-        while next != delimiter && !self.finished {
-            if next == '\n' {
-                self.line += 1;
-            }
-            next = match self.advance() {
-                Some(v) => v,
+        // Advance until the closing delimiter, skipping over
+        // escaped characters (e.g., \" or \\)
+        loop {
+            let (next, escaped) = match self.advance() {
+                Some('\\') => (self.advance(), true),
+                v => (v, false),
+            };
+            match next {
+                Some(c) if c == delimiter && !escaped => break,
+                Some('\n') => self.line += 1,
+                Some(_) => {}
                 None => {
                     self.error_msg = format!("Unterminated string, started at line {}", start_line);
                     return self.make_token(TokenType::Error);
                 }
-            };
+            }
         }
 
         let mut ret = self.make_token(TokenType::TokenString);
@@ -344,6 +379,7 @@ impl<'a> SimpleScanner<'a> {
             '[' => self.make_token(TokenType::LeftBracket),
             ']' => self.make_token(TokenType::RightBracket),
             ',' => self.make_token(TokenType::Comma),
+            ';' => self.make_token(TokenType::Semicolon),
             ':' => {
                 if self.match_char(':') {
                     self.make_token(TokenType::ColonColon)
@@ -369,13 +405,33 @@ impl<'a> SimpleScanner<'a> {
 
             // Error
             _ => {
-                self.error_msg =
-                    make_error_msg(format!("Unexpected character '{}' ", c), self.line);
+                self.error_msg = format!("Unexpected character '{}'", c);
                 self.make_token(TokenType::Error)
             }
         }
     }
 
+    /// Consumes the rest of a `use` statement (i.e., everything up to
+    /// the next `;`) and returns it, trimmed.
+    fn use_statement(&mut self) -> String {
+        let start = self.current_index;
+        let mut end = self.source.len();
+        loop {
+            let token = self.scan_token();
+            match token.token_type {
+                TokenType::Semicolon => {
+                    end = token.start;
+                    break;
+                }
+                TokenType::EOF => break,
+                _ => {}
+            }
+        }
+        String::from_utf8_lossy(&self.source[start..end])
+            .trim()
+            .to_string()
+    }
+
     /// Consumes an object and returns the start and end of that object.
     pub fn get_object_slice(&mut self) -> (usize, usize) {
         let mut levels = 0;
@@ -420,28 +476,42 @@ impl<'a> SimpleScanner<'a> {
         (self.start_index, self.current_index)
     }
 
-    pub fn scan_field(&mut self) -> Result<(Token, Token), String> {
+    /// Builds the error corresponding to finding an `unexpected` [`Token`]
+    fn unexpected_token_error(&self, unexpected: &Token, message: String) -> SimpleModelError {
+        let message = if unexpected.token_type == TokenType::Error {
+            format!("{}... {}", message, self.error_msg)
+        } else {
+            message
+        };
+        SimpleModelError::Syntax {
+            line: unexpected.line,
+            column: unexpected.column,
+            message,
+        }
+    }
+
+    pub fn scan_field(&mut self) -> Result<(Token<'a>, Token<'a>), SimpleModelError> {
         // Scan the name of the field
         let field_name = self.scan_token();
         if field_name.token_type != TokenType::Identifier {
-            return Err(make_error_msg(
+            return Err(self.unexpected_token_error(
+                &field_name,
                 format!(
-                    "uxpecting field identifier, found {:?} '{}'",
+                    "expecting field identifier, found {:?} '{}'",
                     field_name.token_type,
                     std::str::from_utf8(field_name.txt).unwrap()
                 ),
-                self.line,
             ));
         }
 
         let colon = self.scan_token();
         if colon.token_type != TokenType::Colon {
-            return Err(make_error_msg(
+            return Err(self.unexpected_token_error(
+                &colon,
                 format!(
                     "unexpected token '{}'... expecting ':' separating field names and values",
-                    std::str::from_utf8(field_name.txt).unwrap()
+                    std::str::from_utf8(colon.txt).unwrap()
                 ),
-                self.line,
             ));
         }
 
@@ -457,25 +527,25 @@ impl<'a> SimpleScanner<'a> {
     ///
     /// Note that this function is not meant to be
     /// fast, as it intends to search for a field within a small source.
-    pub fn get_field(&mut self) -> Result<(Token, Token), String> {
+    pub fn get_field(&mut self) -> Result<(Token<'a>, Token<'a>), SimpleModelError> {
         let fieldname = self.scan_token();
         if fieldname.token_type != TokenType::Identifier {
-            return Err(make_error_msg(
+            return Err(self.unexpected_token_error(
+                &fieldname,
                 format!(
                     "expecting fieldname to be an Identifier... found '{}'",
                     fieldname.token_type
                 ),
-                self.line,
             ));
         }
         let colon = self.scan_token();
         if colon.token_type != TokenType::Colon {
-            return Err(make_error_msg(
+            return Err(self.unexpected_token_error(
+                &colon,
                 format!(
                     "Expecting colon (':') to separate field names and values... found '{}'",
                     colon.token_type
                 ),
-                self.line,
             ));
         }
         let fieldvalue = self.scan_token();
@@ -483,18 +553,49 @@ impl<'a> SimpleScanner<'a> {
         Ok((fieldname, fieldvalue))
     }
 
-    pub fn parse_model(&mut self) -> Result<(SimpleModel, SimulationStateHeader), String> {
-        // identify elements in the code
-        let mut buildings: Vec<(usize, usize, usize)> = Vec::with_capacity(1);
-        let mut substances: Vec<(usize, usize, usize)> = Vec::new();
-        let mut materials: Vec<(usize, usize, usize)> = Vec::new();
-        let mut constructions: Vec<(usize, usize, usize)> = Vec::new();
+    /// Parses a whole model in strict mode.
+    ///
+    /// If any object cannot be read—or if an unknown type of object is
+    /// found—all the diagnostics are returned and no model is built.
+    pub fn parse_model(
+        &mut self,
+    ) -> Result<(SimpleModel, SimulationStateHeader), Vec<SimpleModelError>> {
+        let (model, state_header, diagnostics) = self.parse_model_lenient();
+        if diagnostics.is_empty() {
+            Ok((model, state_header))
+        } else {
+            Err(diagnostics)
+        }
+    }
+
+    /// Parses a whole model in lenient mode.
+    ///
+    /// Objects that cannot be read are skipped, and the problems found
+    /// are returned as diagnostics together with the (partial) model. The
+    /// returned header is the one of [`SimpleModel::build_state_header`].
+    pub fn parse_model_lenient(
+        &mut self,
+    ) -> (SimpleModel, SimulationStateHeader, Vec<SimpleModelError>) {
+        let mut diagnostics: Vec<SimpleModelError> = Vec::new();
 
-        let mut spaces: Vec<(usize, usize, usize)> = Vec::new();
-        let mut surfaces: Vec<(usize, usize, usize)> = Vec::new();
-        let mut fenestrations: Vec<(usize, usize, usize)> = Vec::new();
-        let mut hvacs: Vec<(usize, usize, usize)> = Vec::new();
-        let mut luminaires: Vec<(usize, usize, usize)> = Vec::new();
+        // identify elements in the code
+        let mut schedules: Vec<ObjectSlice> = Vec::new();
+        let mut buildings: Vec<ObjectSlice> = Vec::with_capacity(1);
+        let mut substances: Vec<ObjectSlice> = Vec::new();
+        let mut materials: Vec<ObjectSlice> = Vec::new();
+        let mut constructions: Vec<ObjectSlice> = Vec::new();
+
+        let mut spaces: Vec<ObjectSlice> = Vec::new();
+        let mut occupants: Vec<ObjectSlice> = Vec::new();
+        let mut shading_devices: Vec<ObjectSlice> = Vec::new();
+        let mut surfaces: Vec<ObjectSlice> = Vec::new();
+        let mut fenestrations: Vec<ObjectSlice> = Vec::new();
+        let mut thermostats: Vec<ObjectSlice> = Vec::new();
+        let mut hvacs: Vec<ObjectSlice> = Vec::new();
+        let mut luminaires: Vec<ObjectSlice> = Vec::new();
+        let mut equipment: Vec<ObjectSlice> = Vec::new();
+        let mut ventilations: Vec<ObjectSlice> = Vec::new();
+        let mut air_exchanges: Vec<ObjectSlice> = Vec::new();
 
         while !self.finished {
             let identifier = self.scan_token();
@@ -502,151 +603,345 @@ impl<'a> SimpleScanner<'a> {
             // Handle case when token is not an identifier
             if identifier.token_type == TokenType::EOF {
                 break;
+            } else if identifier.token_type == TokenType::Use {
+                // Nothing is loaded, but the objects after it can still be read
+                let statement = self.use_statement();
+                diagnostics.push(SimpleModelError::UnsupportedUse {
+                    line: identifier.line,
+                    column: identifier.column,
+                    statement,
+                });
+                continue;
             } else if identifier.token_type != TokenType::Identifier
                 && identifier.token_type != TokenType::TokenEnumName
             {
-                return Err(make_error_msg(
+                // We cannot know where the next object starts... stop here
+                diagnostics.push(self.unexpected_token_error(
+                    &identifier,
                     format!("Unexpected token of type {:?}", identifier.token_type),
-                    self.line,
                 ));
+                break;
             }
             let line = self.line;
             let (start, end) = self.get_object_slice();
-            match identifier.txt {
-                b"Building" => {
-                    buildings.push((line, start, end));
-                }
-                b"Substance" => {
-                    substances.push((line, start, end));
-                }
-                b"Material" => {
-                    materials.push((line, start, end));
-                }
-                b"Construction" => {
-                    constructions.push((line, start, end));
-                }
-                b"Space" => {
-                    spaces.push((line, start, end));
-                }
-                b"Surface" => {
-                    surfaces.push((line, start, end));
-                }
-                b"Fenestration" => {
-                    fenestrations.push((line, start, end));
-                }
-                b"HVAC" => {
-                    hvacs.push((line, start, end));
-                }
-                b"Luminaire" => {
-                    luminaires.push((line, start, end));
-                }
+            let (object_type, target) = match identifier.txt {
+                b"Schedule" => ("Schedule", &mut schedules),
+                b"Building" => ("Building", &mut buildings),
+                b"Substance" => ("Substance", &mut substances),
+                b"Material" => ("Material", &mut materials),
+                b"Construction" => ("Construction", &mut constructions),
+                b"Space" => ("Space", &mut spaces),
+                b"Occupant" => ("Occupant", &mut occupants),
+                b"Surface" => ("Surface", &mut surfaces),
+                b"ShadingDevice" => ("ShadingDevice", &mut shading_devices),
+                b"Fenestration" => ("Fenestration", &mut fenestrations),
+                b"Thermostat" => ("Thermostat", &mut thermostats),
+                b"HVAC" => ("HVAC", &mut hvacs),
+                b"Luminaire" => ("Luminaire", &mut luminaires),
+                b"Equipment" => ("Equipment", &mut equipment),
+                b"Ventilation" => ("Ventilation", &mut ventilations),
+                b"AirExchange" => ("AirExchange", &mut air_exchanges),
                 _ => {
                     let typename = std::str::from_utf8(identifier.txt).unwrap();
-                    eprintln!(
-                        "{}",
-                        make_error_msg(format!("Unknown Object type {}", typename), self.line)
-                    );
+                    diagnostics.push(SimpleModelError::UnknownObjectType {
+                        line: identifier.line,
+                        column: identifier.column,
+                        object_type: typename.to_string(),
+                    });
+                    continue;
                 }
-            }
+            };
+            target.push(ObjectSlice {
+                object_type,
+                name: Self::find_object_name(self.borrow_slice(start, end)),
+                line,
+                start,
+                end,
+            });
         }
 
         // NOW BUILD
         let mut model = SimpleModel::new("the_model".to_string());
         let mut state_header = SimulationStateHeader::new();
 
-        for (line, start, end) in buildings {
-            let bytes = self.borrow_slice(start, end);
-            match Building::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_building(s);
+        // Objects are attempted in this order, which is the order
+        // in which they usually reference each other
+        let mut pending: Vec<ObjectSlice> = Vec::new();
+        pending.extend(schedules);
+        pending.extend(buildings);
+        pending.extend(substances);
+        pending.extend(materials);
+        pending.extend(constructions);
+        pending.extend(spaces);
+        // Occupants push personal elements, which go before all others (this
+        // is not required, but it avoids reordering the state header)
+        pending.extend(occupants);
+        pending.extend(surfaces);
+        pending.extend(shading_devices);
+        pending.extend(fenestrations);
+        pending.extend(thermostats);
+        pending.extend(hvacs);
+        pending.extend(luminaires);
+        pending.extend(equipment);
+        pending.extend(ventilations);
+        // Air exchanges push physical elements, which go after all others
+        pending.extend(air_exchanges);
+        let declared: Vec<(&'static str, Option<String>)> = pending
+            .iter()
+            .map(|o| (o.object_type, o.name.clone()))
+            .collect();
+
+        // Objects that reference other objects that have not been built yet
+        // are deferred, recording the object they are waiting for. These are
+        // the edges of the dependency graph: whenever an object is built, the
+        // objects waiting for it are queued again, so everything is built in
+        // topological order.
+        let mut queue: VecDeque<ObjectSlice> = pending.into();
+        let mut waiting: HashMap<(&'static str, String), Vec<(ObjectSlice, SimpleModelError)>> =
+            HashMap::new();
+        while let Some(object) = queue.pop_front() {
+            match self.build_object(&object, &mut model, &mut state_header) {
+                Ok(()) => {
+                    if let Some(name) = &object.name {
+                        if let Some(dependents) =
+                            waiting.remove(&(object.object_type, name.clone()))
+                        {
+                            queue.extend(dependents.into_iter().map(|(o, _)| o));
+                        }
+                    }
                 }
-                Err(e) => eprintln!("{}", e),
-            };
-        }
-
-        for (line, start, end) in substances {
-            let bytes = self.borrow_slice(start, end);
-            match Substance::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_substance(s);
+                Err(e) => {
+                    // Wait only for objects that are declared somewhere
+                    let waiting_for = match &e {
+                        SimpleModelError::UnknownReference {
+                            reference_type,
+                            reference_name,
+                            ..
+                        } => declared
+                            .iter()
+                            .find(|(t, n)| {
+                                t == reference_type && n.as_deref() == Some(reference_name.as_str())
+                            })
+                            .map(|(t, _)| (*t, reference_name.clone())),
+                        _ => None,
+                    };
+                    match waiting_for {
+                        Some(key) => waiting.entry(key).or_default().push((object, e)),
+                        None => diagnostics.push(e),
+                    }
                 }
-                Err(e) => eprintln!("{}", e),
-            };
+            }
         }
 
-        for (line, start, end) in materials {
-            let bytes = self.borrow_slice(start, end);
-            match Material::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_material(s);
-                }
-                Err(e) => eprintln!("{}", e),
-            };
-        }
+        // Whatever is still waiting depends on objects that could not be built
+        let mut blocked: Vec<(ObjectSlice, SimpleModelError)> =
+            waiting.into_values().flatten().collect();
+        blocked.sort_by_key(|(o, _)| (o.line, o.start));
+        diagnostics.extend(Self::report_blocked(blocked));
 
-        for (line, start, end) in constructions {
-            let bytes = self.borrow_slice(start, end);
-            match Construction::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_construction(s);
-                }
-                Err(e) => eprintln!("{}", e),
-            };
-        }
+        // The header is built again from the whole model, so parsing gives
+        // the same layout as SimpleModel::build_state_header()
+        let state_header = model.build_state_header();
 
-        for (line, start, end) in spaces {
-            let bytes = self.borrow_slice(start, end);
-            match Space::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_space(s);
-                }
-                Err(e) => eprintln!("{}", e),
-            };
-        }
+        // Return
+        (model, state_header, diagnostics)
+    }
 
-        for (line, start, end) in surfaces {
-            let bytes = self.borrow_slice(start, end);
-            match Surface::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_surface(s);
-                }
-                Err(e) => eprintln!("{}", e),
-            };
+    /// Builds an object and adds it to the model
+    fn build_object(
+        &self,
+        object: &ObjectSlice,
+        model: &mut SimpleModel,
+        state_header: &mut SimulationStateHeader,
+    ) -> Result<(), SimpleModelError> {
+        let line = object.line;
+        let bytes = self.borrow_slice(object.start, object.end);
+        let shift = |e: SimpleModelError| e.shift_column(line, self.column(object.start) - 1);
+        match object.object_type {
+            "Schedule" => {
+                let s = Schedule::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_schedule(s)?;
+            }
+            "Building" => {
+                let s = Building::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_building(s);
+            }
+            "Substance" => {
+                let s = Substance::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_substance(s);
+            }
+            "Material" => {
+                let s = Material::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_material(s);
+            }
+            "Construction" => {
+                let s = Construction::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_construction(s);
+            }
+            "Space" => {
+                let s = Space::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_space(s);
+            }
+            "Occupant" => {
+                let s = Occupant::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_occupant(s, state_header);
+            }
+            "Surface" => {
+                let s = Surface::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_surface(s);
+            }
+            "ShadingDevice" => {
+                let s = ShadingDevice::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_shading_device(s, state_header);
+            }
+            "Fenestration" => {
+                let s = Fenestration::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_fenestration(s, state_header);
+            }
+            "Thermostat" => {
+                let s = Thermostat::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_thermostat(s, state_header);
+            }
+            "HVAC" => {
+                let s = HVAC::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_hvac(s, state_header);
+            }
+            "Luminaire" => {
+                let s = Luminaire::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_luminaire(s, state_header);
+            }
+            "Equipment" => {
+                let s = Equipment::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_equipment(s, state_header);
+            }
+            "Ventilation" => {
+                let s = Ventilation::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_ventilation(s, state_header);
+            }
+            "AirExchange" => {
+                let s = AirExchange::from_bytes(line, bytes, model).map_err(shift)?;
+                model.add_air_exchange(s, state_header);
+            }
+            _ => unreachable!(),
         }
+        Ok(())
+    }
 
-        for (line, start, end) in fenestrations {
-            let bytes = self.borrow_slice(start, end);
-            match Fenestration::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_fenestration(s, &mut state_header);
+    /// Reports the objects that could not be built because they were
+    /// waiting for each other. Cycles in the dependency graph are
+    /// reported as [`SimpleModelError::ReferenceCycle`]; the objects
+    /// that depend on those cycles report the reference they could
+    /// not resolve.
+    fn report_blocked(blocked: Vec<(ObjectSlice, SimpleModelError)>) -> Vec<SimpleModelError> {
+        // Each blocked object is waiting for one other blocked object
+        let waiting_for: Vec<Option<usize>> = blocked
+            .iter()
+            .map(|(_, e)| match e {
+                SimpleModelError::UnknownReference {
+                    reference_type,
+                    reference_name,
+                    ..
+                } => blocked
+                    .iter()
+                    .position(|(o, _)| o.is(reference_type, reference_name)),
+                _ => None,
+            })
+            .collect();
+
+        let mut ret = Vec::new();
+        let mut in_cycle = vec![false; blocked.len()];
+        let mut visited = vec![false; blocked.len()];
+        for first in 0..blocked.len() {
+            // Follow the references until we find something we have seen
+            let mut path: Vec<usize> = Vec::new();
+            let mut current = Some(first);
+            while let Some(i) = current {
+                if visited[i] {
+                    if let Some(cycle_start) = path.iter().position(|p| *p == i) {
+                        let cycle = &path[cycle_start..];
+                        for i in cycle {
+                            in_cycle[*i] = true;
+                        }
+                        ret.push(SimpleModelError::ReferenceCycle {
+                            objects: cycle
+                                .iter()
+                                .map(|i| {
+                                    let o = &blocked[*i].0;
+                                    (
+                                        o.object_type.to_string(),
+                                        o.name.clone().unwrap_or_default(),
+                                        o.line,
+                                    )
+                                })
+                                .collect(),
+                        });
+                    }
+                    break;
                 }
-                Err(e) => eprintln!("{}", e),
-            };
+                visited[i] = true;
+                path.push(i);
+                current = waiting_for[i];
+            }
         }
 
-        for (line, start, end) in hvacs {
-            let bytes = self.borrow_slice(start, end);
-            match HVAC::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_hvac(s, &mut state_header);
-                }
-                Err(e) => eprintln!("{}", e),
-            };
+        for (i, (_, e)) in blocked.into_iter().enumerate() {
+            if !in_cycle[i] {
+                ret.push(e)
+            }
         }
+        ret
+    }
 
-        for (line, start, end) in luminaires {
-            let bytes = self.borrow_slice(start, end);
-            match Luminaire::from_bytes(line, bytes, &model) {
-                Ok(s) => {
-                    model.add_luminaire(s, &mut state_header);
+    /// Finds the value of the `name` field of an object, without building it
+    fn find_object_name(bytes: &[u8]) -> Option<String> {
+        let mut scanner = SimpleScanner::new(bytes, 1);
+        let mut depth = 0;
+        loop {
+            let token = scanner.scan_token();
+            match token.token_type {
+                TokenType::EOF => return None,
+                TokenType::LeftBrace | TokenType::LeftBracket | TokenType::LeftParen => depth += 1,
+                TokenType::RightBrace | TokenType::RightBracket | TokenType::RightParen => {
+                    depth -= 1
                 }
-                Err(e) => eprintln!("{}", e),
-            };
+                TokenType::Identifier
+                    if depth == 1
+                        && token.txt == b"name"
+                        && scanner.scan_token().token_type == TokenType::Colon =>
+                {
+                    return scanner.scan_token().resolve_as_string().ok();
+                }
+                _ => {}
+            }
         }
-        // Return
-        Ok((model, state_header))
     }
 }
+
+/// An object found in the source, which has not been built yet
+struct ObjectSlice {
+    /// The type of object (e.g., `"Space"`)
+    object_type: &'static str,
+
+    /// The name of the object, if it has one
+    name: Option<String>,
+
+    /// The line in which the object starts
+    line: usize,
+
+    /// The start of the object in the source
+    start: usize,
+
+    /// The end of the object in the source
+    end: usize,
+}
+
+impl ObjectSlice {
+    /// Checks whether this is the object of a certain type and name
+    fn is(&self, object_type: &str, object_name: &str) -> bool {
+        self.object_type == object_type && self.name.as_deref() == Some(object_name)
+    }
+}
+
 /***********/
 /* TESTING */
 /***********/
@@ -654,6 +949,7 @@ impl<'a> SimpleScanner<'a> {
 #[cfg(test)]
 mod testing {
     use super::*;
+    use std::rc::Rc;
 
     #[test]
     fn test_scanner_advance() {
@@ -914,4 +1210,331 @@ mod testing {
 
         assert!(scanner.finished);
     }
+
+    #[test]
+    fn test_error_positions() {
+        let model = SimpleModel::default();
+
+        // Reference to an object that does not exist
+        let bytes = b" {
+    name: \"the material\",
+    substance: \"the substance\",
+    thickness: 0.1
+}";
+        match Material::from_bytes(1, bytes, &model) {
+            Err(SimpleModelError::UnknownReference {
+                line,
+                column,
+                reference_type,
+                reference_name,
+                object_type,
+                object_name,
+                field,
+            }) => {
+                assert_eq!((line, column), (3, 16));
+                assert_eq!(reference_type, "Substance");
+                assert_eq!(reference_name, "the substance");
+                assert_eq!(object_type, Some("Material"));
+                assert_eq!(object_name.as_deref(), Some("the material"));
+                assert_eq!(field, Some("substance"));
+            }
+            _ => panic!("Expecting an UnknownReference error"),
+        }
+
+        // Missing field
+        let bytes = b" {
+    name: \"the material\"
+}";
+        match Material::from_bytes(1, bytes, &model) {
+            Err(SimpleModelError::MissingField {
+                line,
+                column,
+                object_type,
+                object_name,
+                field,
+            }) => {
+                // Reported where the object opens
+                assert_eq!((line, column), (1, 2));
+                assert_eq!(object_type, "Material");
+                assert_eq!(object_name, Some("the material".to_string()));
+                assert_eq!(field, "substance");
+            }
+            _ => panic!("Expecting a MissingField error"),
+        }
+
+        // Bad number
+        let bytes = b" {
+    name: \"Kitchen\",
+    volume: \"big\"
+}";
+        match Space::from_bytes(1, bytes, &model) {
+            Err(SimpleModelError::BadNumber {
+                line,
+                column,
+                object_type,
+                object_name,
+                field,
+                ..
+            }) => {
+                assert_eq!((line, column), (3, 13));
+                assert_eq!(object_type, Some("Space"));
+                assert_eq!(object_name.as_deref(), Some("Kitchen"));
+                assert_eq!(field, Some("volume"));
+            }
+            _ => panic!("Expecting a BadNumber error"),
+        }
+
+        // Errors in objects that start in the middle of a line
+        let bytes = b" {
+    name: \"Kitchen\",
+    infiltration: Infiltration::Bogus(2)
+}";
+        match Space::from_bytes(1, bytes, &model) {
+            Err(SimpleModelError::UnknownObjectType {
+                line,
+                column,
+                object_type,
+            }) => {
+                assert_eq!((line, column), (3, 33));
+                assert_eq!(object_type, "Infiltration::Bogus");
+            }
+            _ => panic!("Expecting an UnknownObjectType error"),
+        }
+    }
+
+    #[test]
+    fn test_strict_and_lenient() {
+        let src = b"
+Substance::Normal {
+    name: \"the substance\"
+}
+
+Material {
+    name: \"broken material\",
+    substance: \"not there\",
+    thickness: 0.1
+}
+
+Material {
+    name: \"good material\",
+    substance: \"the substance\",
+    thickness: 0.1
+}
+
+Spaceship {
+    name: \"Enterprise\"
+}
+";
+        // Strict
+        let errors = match SimpleScanner::new(src, 1).parse_model() {
+            Ok(_) => panic!("Expecting errors"),
+            Err(e) => e,
+        };
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            errors[0],
+            SimpleModelError::UnknownObjectType { line: 18, .. }
+        ));
+        assert!(matches!(
+            errors[1],
+            SimpleModelError::UnknownReference { line: 8, .. }
+        ));
+
+        // Lenient
+        let (model, _header, diagnostics) = SimpleScanner::new(src, 1).parse_model_lenient();
+        assert_eq!(diagnostics, errors);
+        assert_eq!(model.materials.len(), 1);
+        assert_eq!(model.materials[0].name, "good material");
+    }
+
+    #[test]
+    fn test_use_statement() {
+        let src = b"
+use some_db::concrete as \"con;crete\";
+Substance::Normal {
+    name: \"the concrete\"
+}
+";
+        let (model, _header, diagnostics) = SimpleScanner::new(src, 1).parse_model_lenient();
+        assert_eq!(
+            diagnostics,
+            vec![SimpleModelError::UnsupportedUse {
+                line: 2,
+                column: 1,
+                statement: "some_db::concrete as \"con;crete\"".to_string(),
+            }]
+        );
+        // The objects after it are still read
+        assert_eq!(model.substances.len(), 1);
+        assert_eq!(model.substances[0].name(), "the concrete");
+        assert!(SimpleScanner::new(src, 1).parse_model().is_err());
+    }
+
+    #[test]
+    fn test_find_object_name() {
+        let name = SimpleScanner::find_object_name(
+            b" {
+            construction: Construction {
+                name: \"inline construction\"
+            },
+            name: \"the surface\"
+        }",
+        );
+        assert_eq!(name, Some("the surface".to_string()));
+
+        let name = SimpleScanner::find_object_name(b"::Normal { name: \"the substance\" }");
+        assert_eq!(name, Some("the substance".to_string()));
+
+        assert!(SimpleScanner::find_object_name(b" { volume: 2 }").is_none());
+    }
+
+    #[test]
+    fn test_reverse_order() {
+        // Everything is declared before the objects it references
+        let src = b"
+Surface {
+    name: \"the surface\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 1, 0],
+    front_boundary: Boundary::Space(\"the space\")
+}
+Space {
+    name: \"the space\"
+}
+Construction {
+    name: \"the construction\",
+    materials: [\"the material\"]
+}
+Material {
+    name: \"the material\",
+    substance: \"the substance\",
+    thickness: 0.1
+}
+Substance::Normal {
+    name: \"the substance\"
+}
+";
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.surfaces.len(), 1);
+        assert_eq!(model.constructions[0].materials[0].name, "the material");
+    }
+
+    #[test]
+    fn test_forward_reference_same_type() {
+        // The mixture references gases declared after it, and the
+        // Surface references the last one of several Spaces
+        let src = b"
+Substance::Gas {
+    name: \"argon and air\",
+    mixture_components: [\"argon\", \"air\"],
+    mixture_fractions: [9, 1]
+}
+Surface {
+    name: \"the surface\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 1, 0],
+    front_boundary: Boundary::Space(\"C\")
+}
+Space {
+    name: \"A\"
+}
+Space {
+    name: \"B\"
+}
+Substance::Gas {
+    name: \"argon\",
+    kind: StandardGas::Argon()
+}
+Construction {
+    name: \"the construction\",
+    materials: [\"the material\"]
+}
+Material {
+    name: \"the material\",
+    substance: \"air\",
+    thickness: 0.1
+}
+Space {
+    name: \"C\"
+}
+Substance::Gas {
+    name: \"air\",
+    kind: StandardGas::Air()
+}
+";
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+
+        // Objects are built after the objects they reference
+        let names: Vec<&str> = model.substances.iter().map(|s| s.name().as_str()).collect();
+        assert_eq!(names, vec!["argon", "air", "argon and air"]);
+        let names: Vec<&str> = model.spaces.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["A", "B", "C"]);
+        match model.surfaces[0].front_boundary() {
+            Ok(crate::Boundary::Space(s)) => assert!(Rc::ptr_eq(s, &model.spaces[2])),
+            _ => panic!("Expecting a Space boundary"),
+        }
+
+        // Mixtures of each other can never be built
+        let src = b"
+Substance::Gas {
+    name: \"A\",
+    mixture_components: [\"B\"],
+    mixture_fractions: [1]
+}
+Substance::Gas {
+    name: \"B\",
+    mixture_components: [\"A\"],
+    mixture_fractions: [1]
+}
+";
+        let (_model, _header, errors) = SimpleScanner::new(src, 1).parse_model_lenient();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            SimpleModelError::ReferenceCycle { objects } if objects.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_report_blocked() {
+        let object = |object_type: &'static str, name: &str, line: usize| ObjectSlice {
+            object_type,
+            name: Some(name.to_string()),
+            line,
+            start: 0,
+            end: 0,
+        };
+        let reference = |object_type: &str, object_name: &str| SimpleModelError::UnknownReference {
+            line: 1,
+            column: 1,
+            reference_type: object_type.to_string(),
+            reference_name: object_name.to_string(),
+            object_type: None,
+            object_name: None,
+            field: None,
+        };
+
+        // C -> A -> B -> A
+        let blocked = vec![
+            (object("Surface", "C", 20), reference("Space", "A")),
+            (object("Space", "A", 3), reference("Surface", "B")),
+            (object("Surface", "B", 10), reference("Space", "A")),
+        ];
+        let errors = SimpleScanner::report_blocked(blocked);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            SimpleModelError::ReferenceCycle {
+                objects: vec![
+                    ("Space".to_string(), "A".to_string(), 3),
+                    ("Surface".to_string(), "B".to_string(), 10),
+                ]
+            }
+        );
+        assert_eq!(errors[1], reference("Space", "A"));
+        assert_eq!(
+            errors[0].to_string(),
+            "Error: objects reference each other in a cycle: Space 'A' (line 3) references Surface 'B' (line 10) references Space 'A'"
+        );
+    }
 }
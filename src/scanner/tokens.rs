@@ -18,12 +18,28 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
-use crate::scanner::make_error_msg;
 use crate::Float;
+use crate::SimpleModelError;
+
+/// Wraps a `str` in double quotes, escaping the backslashes and
+/// quotes within it so that the scanner can read it back.
+pub fn quote_string(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len() + 2);
+    ret.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            ret.push('\\');
+        }
+        ret.push(c);
+    }
+    ret.push('"');
+    ret
+}
 
 #[derive(Clone, Copy)]
 pub struct Token<'a> {
     pub line: usize,
+    pub column: usize,
     pub length: usize,
     pub start: usize,
     pub token_type: TokenType,
@@ -35,6 +51,7 @@ pub enum TokenType {
     // single char
     Colon,
     Comma,
+    Semicolon,
     LeftBrace,
     RightBrace,
     LeftBracket,
@@ -81,6 +98,7 @@ impl std::fmt::Display for TokenType {
             // Single char
             TokenType::Colon => write!(f, ":"),
             TokenType::Comma => write!(f, ","),
+            TokenType::Semicolon => write!(f, ";"),
             TokenType::LeftBrace => write!(f, "{{"),
             TokenType::RightBrace => write!(f, "}}"),
             TokenType::LeftBracket => write!(f, "["),
@@ -124,60 +142,98 @@ impl std::fmt::Display for TokenType {
 }
 
 impl<'a> Token<'a> {
-    pub fn resolve_as_bool(&self) -> Result<bool, String> {
+    pub fn resolve_as_bool(&self) -> Result<bool, SimpleModelError> {
         let txt = std::str::from_utf8(self.txt).unwrap();
         match self.token_type {
             TokenType::True => Ok(true),
             TokenType::False => Ok(false),
-            _ => Err(format!(
-                "Trying to make a Boolean a token of type '{}' ({})",
-                self.token_type, txt
-            )),
+            _ => Err(SimpleModelError::InvalidValue {
+                line: self.line,
+                column: self.column,
+                expected: "a Boolean (i.e., 'true' or 'false')".to_string(),
+                found: format!("a token of type '{}' ({})", self.token_type, txt),
+                object_type: None,
+                object_name: None,
+                field: None,
+            }),
         }
     }
 
-    pub fn resolve_as_float(&self) -> Result<Float, String> {
+    pub fn resolve_as_float(&self) -> Result<Float, SimpleModelError> {
         let txt = std::str::from_utf8(self.txt).unwrap();
+        let err = SimpleModelError::BadNumber {
+            line: self.line,
+            column: self.column,
+            value: txt.to_string(),
+            expected: "float".to_string(),
+            object_type: None,
+            object_name: None,
+            field: None,
+        };
         if let TokenType::Number = self.token_type {
-            match txt.parse::<Float>() {
-                Ok(fvalue) => Ok(fvalue),
-                Err(e) => Err(format!("This is a bug, please report it: {}", e)),
-            }
+            txt.parse::<Float>().map_err(|_| err)
         } else {
-            Err(format!(
-                "Token '{}' cannot be transformed into a float",
-                txt
-            ))
+            Err(err)
         }
     }
 
-    pub fn resolve_as_usize(&self) -> Result<usize, String> {
+    pub fn resolve_as_usize(&self) -> Result<usize, SimpleModelError> {
         let txt = std::str::from_utf8(self.txt).unwrap();
         if let TokenType::Number = self.token_type {
-            match txt.parse::<usize>(){
-                 Ok(fvalue)=>Ok(fvalue),
-                 Err(_)=>Err(make_error_msg(format!("value '{}' does not seem to be a positive integer. Hint: remove dots, e.g., don't write '12.', write '12'", txt), self.line))
-             }
+            match txt.parse::<usize>() {
+                Ok(fvalue) => Ok(fvalue),
+                // e.g., '12.' instead of '12'
+                Err(_) => Err(SimpleModelError::BadNumber {
+                    line: self.line,
+                    column: self.column,
+                    value: txt.to_string(),
+                    expected:
+                        "positive integer (Hint: remove dots, e.g., don't write '12.', write '12')"
+                            .to_string(),
+                    object_type: None,
+                    object_name: None,
+                    field: None,
+                }),
+            }
         } else {
-            Err(make_error_msg(
-                format!(
-                    "Token '{}' cannot be transformed into a positive integer",
-                    txt
-                ),
-                self.line,
-            ))
+            Err(SimpleModelError::BadNumber {
+                line: self.line,
+                column: self.column,
+                value: txt.to_string(),
+                expected: "positive integer".to_string(),
+                object_type: None,
+                object_name: None,
+                field: None,
+            })
         }
     }
 
-    pub fn resolve_as_string(&self) -> Result<String, String> {
+    pub fn resolve_as_string(&self) -> Result<String, SimpleModelError> {
         let txt = std::str::from_utf8(self.txt).unwrap();
         if let TokenType::TokenString = self.token_type {
-            Ok(txt[1..txt.len() - 1].to_string())
+            // Remove the quotes, and the backslashes that escape characters
+            let mut ret = String::with_capacity(txt.len() - 2);
+            let mut chars = txt[1..txt.len() - 1].chars();
+            while let Some(c) = chars.next() {
+                if c == '\\' {
+                    if let Some(escaped) = chars.next() {
+                        ret.push(escaped);
+                    }
+                } else {
+                    ret.push(c);
+                }
+            }
+            Ok(ret)
         } else {
-            Err(make_error_msg(
-                format!("Token '{}' cannot be transformed into a String", txt),
-                self.line,
-            ))
+            Err(SimpleModelError::InvalidValue {
+                line: self.line,
+                column: self.column,
+                expected: "a String".to_string(),
+                found: format!("'{}'", txt),
+                object_type: None,
+                object_name: None,
+                field: None,
+            })
         }
     }
 }
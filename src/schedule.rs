@@ -0,0 +1,339 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::model::SimpleModel;
+use crate::{Float, SimpleModelError};
+use calendar::Date;
+use derive::ObjectIO;
+use std::rc::Rc;
+
+/// The number of days before the first day of each month (in a non-leap year)
+const DAYS_BEFORE_MONTH: [usize; 12] = [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+
+/// The number of days in each month (in a non-leap year)
+const DAYS_IN_MONTH: [usize; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// The values that a [`Schedule`] takes over time
+#[derive(Clone, ObjectIO)]
+pub enum ScheduleProfile {
+    /// The same value at all times
+    Constant(Float),
+
+    /// 24 hourly values (i.e., from 0:00 to 1:00, from 1:00 to
+    /// 2:00, and so on) that are repeated every day
+    Daily(Vec<Float>),
+
+    /// 24 hourly values for weekdays (i.e., Monday to Friday), followed
+    /// by 24 hourly values for Saturdays and 24 hourly values for Sundays
+    Weekly(Vec<Float>, Vec<Float>, Vec<Float>),
+}
+
+/// A value that changes over time (e.g., the availability of a
+/// [`Luminaire`](crate::Luminaire)), which is referenced by name by other objects.
+///
+/// It is checked when added to the [`SimpleModel`], and its values
+/// are given through the [`schedule::Schedule`] trait.
+///
+/// ### Example
+///
+/// ```rs
+/// Schedule {
+///     name: "Office hours",
+///     profile: ScheduleProfile::Weekly(
+///         [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0],
+///         [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+///         [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+///     ),
+///     holidays: [[1, 1], [12, 25]], // January 1st and December 25th
+///     holiday_profile: ScheduleProfile::Constant(0)
+/// }
+///
+/// Luminaire {
+///     name: "Office lights",
+///     max_power: 200,
+///     availability: "Office hours"
+/// }
+/// ```
+#[derive(ObjectIO, Clone)]
+pub struct Schedule {
+    /// The name of the schedule
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The values of the schedule
+    pub profile: ScheduleProfile,
+
+    /// The holidays, given as `[month, day]` (e.g., `[[1, 1], [12, 25]]`
+    /// for January 1st and December 25th)
+    holidays: Option<Vec<Date>>,
+
+    /// The values of the schedule during holidays. If not
+    /// given, holidays are treated as Sundays.
+    holiday_profile: Option<ScheduleProfile>,
+
+    /// The day of the week of January 1st, from 1 (Monday)
+    /// to 7 (Sunday). Defaults to Monday.
+    first_weekday: Option<usize>,
+}
+
+impl ScheduleProfile {
+    /// Checks that the profile has the right number of values
+    fn check(&self) -> Result<(), String> {
+        let check_day = |values: &Vec<Float>| {
+            if values.len() == 24 {
+                Ok(())
+            } else {
+                Err(format!(
+                    "daily profiles must have 24 hourly values, but one has {}",
+                    values.len()
+                ))
+            }
+        };
+        match self {
+            Self::Constant(_) => Ok(()),
+            Self::Daily(day) => check_day(day),
+            Self::Weekly(weekday, saturday, sunday) => {
+                check_day(weekday)?;
+                check_day(saturday)?;
+                check_day(sunday)
+            }
+        }
+    }
+
+    /// Gets the value at a certain `hour` (from 0 to 23) of a certain
+    /// `weekday` (from 0, Monday, to 6, Sunday). The profile must have
+    /// been checked when its [`Schedule`] was added to the model.
+    fn value(&self, weekday: usize, hour: usize) -> Float {
+        match self {
+            Self::Constant(v) => *v,
+            Self::Daily(day) => day[hour],
+            Self::Weekly(weekday_values, saturday, sunday) => match weekday {
+                5 => saturday[hour],
+                6 => sunday[hour],
+                _ => weekday_values[hour],
+            },
+        }
+    }
+}
+
+impl Schedule {
+    /// Checks that the [`Schedule`] is well defined (i.e., that
+    /// its profiles have 24 values per day, and its holidays are
+    /// valid dates).
+    pub fn check(&self) -> Result<(), SimpleModelError> {
+        let invalid = |message: String| SimpleModelError::InvalidProperty {
+            object_type: "Schedule".to_string(),
+            object_name: self.name.clone(),
+            message,
+        };
+        self.profile.check().map_err(invalid)?;
+        if let Ok(profile) = self.holiday_profile() {
+            profile.check().map_err(invalid)?;
+        }
+        if let Ok(holidays) = self.holidays() {
+            for date in holidays {
+                let (month, day) = (date.month as usize, date.day as usize);
+                if !(1..=12).contains(&month) || !(1..=DAYS_IN_MONTH[month - 1]).contains(&day) {
+                    return Err(invalid(format!(
+                        "{}/{} is not a valid holiday (month/day)",
+                        month, day
+                    )));
+                }
+            }
+        }
+        if let Ok(first_weekday) = self.first_weekday() {
+            if *first_weekday < 1 || *first_weekday > 7 {
+                return Err(invalid(format!(
+                    "first_weekday must be between 1 and 7, but it is {}",
+                    first_weekday
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks whether a [`Date`] is one of the holidays
+    fn is_holiday(&self, date: &Date) -> bool {
+        match self.holidays() {
+            Ok(holidays) => holidays
+                .iter()
+                .any(|d| d.month == date.month && d.day == date.day),
+            Err(_) => false,
+        }
+    }
+}
+
+impl ::schedule::Schedule<Float> for Schedule {
+    /// Gets the value of the [`Schedule`] at a certain [`Date`].
+    ///
+    /// The day of the week is calculated from the `first_weekday`,
+    /// assuming a non-leap year.
+    fn get(&self, date: Date) -> Float {
+        let month = (date.month as usize).clamp(1, 12);
+        let day_of_year = DAYS_BEFORE_MONTH[month - 1] + (date.day as usize).max(1) - 1;
+        let first_weekday = self.first_weekday().map(|d| d - 1).unwrap_or(0);
+        let weekday = (first_weekday + day_of_year) % 7;
+        let hour = (date.hour.floor() as usize).min(23);
+
+        if self.is_holiday(&date) {
+            match self.holiday_profile() {
+                Ok(profile) => profile.value(weekday, hour),
+                Err(_) => self.profile.value(6, hour),
+            }
+        } else {
+            self.profile.value(weekday, hour)
+        }
+    }
+}
+
+impl SimpleModel {
+    /// Adds a [`Schedule`] to the [`SimpleModel`], after checking
+    /// that it is well defined
+    pub fn add_schedule(&mut self, mut add: Schedule) -> Result<Rc<Schedule>, SimpleModelError> {
+        add.check()?;
+        add.set_index(self.schedules.len());
+        let add = Rc::new(add);
+        self.schedules.push(Rc::clone(&add));
+        Ok(add)
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+    use ::schedule::Schedule as _;
+
+    fn date(month: u8, day: u8, hour: f64) -> Date {
+        Date { month, day, hour }
+    }
+
+    #[test]
+    fn test_schedule_values() {
+        let src = b"
+Schedule {
+    name: \"always on\",
+    profile: ScheduleProfile::Constant(1)
+}
+
+Schedule {
+    name: \"office\",
+    profile: ScheduleProfile::Weekly(
+        [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0, 0],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0.5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    ),
+    holidays: [[12, 25]],
+    first_weekday: 7
+}
+
+Luminaire {
+    name: \"the lights\",
+    max_power: 100,
+    availability: \"office\"
+}
+
+Space {
+    name: \"the office\",
+    importance: \"office\"
+}
+";
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.schedules.len(), 2);
+
+        let always_on = &model.schedules[0];
+        assert_eq!(always_on.get(date(7, 3, 13.5)), 1.);
+
+        // January 1st is a Sunday, so January 2nd is a Monday
+        let office = &model.schedules[1];
+        assert_eq!(office.get(date(1, 1, 10.)), 0.);
+        assert_eq!(office.get(date(1, 2, 10.)), 1.);
+        assert_eq!(office.get(date(1, 2, 20.)), 0.);
+        assert_eq!(office.get(date(1, 7, 12.2)), 0.5);
+        // December 25th is a Monday, but a holiday
+        assert_eq!(office.get(date(12, 25, 10.)), 0.);
+        assert_eq!(office.get(date(12, 26, 10.)), 1.);
+
+        // Referenced by name
+        let availability = model.luminaires[0].availability().unwrap();
+        assert!(Rc::ptr_eq(availability, office));
+        let importance = model.spaces[0].importance().unwrap();
+        assert!(Rc::ptr_eq(importance, office));
+
+        // Holidays are written back as dates
+        let (written, _header) = SimpleScanner::new(model.to_spl().as_bytes(), 1)
+            .parse_model()
+            .unwrap();
+        assert!(office.to_spl().contains("[[12, 25]]"));
+        assert_eq!(written.schedules[1].get(date(12, 25, 10.)), 0.);
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        let errors = |src: &[u8]| -> Vec<SimpleModelError> {
+            let (_model, _header, diagnostics) = SimpleScanner::new(src, 1).parse_model_lenient();
+            diagnostics
+        };
+
+        // Schedules are checked when they are added to the model
+        let found = errors(
+            b"Schedule {
+                name: \"too short\",
+                profile: ScheduleProfile::Daily([1, 2, 3])
+            }",
+        );
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0], SimpleModelError::InvalidProperty { .. }));
+
+        let found = errors(
+            b"Schedule {
+                name: \"no such day\",
+                profile: ScheduleProfile::Constant(1),
+                holidays: [[2, 30]]
+            }",
+        );
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0], SimpleModelError::InvalidProperty { .. }));
+
+        let found = errors(
+            b"Schedule {
+                name: \"no such month\",
+                profile: ScheduleProfile::Constant(1),
+                holidays: [[13, 1]]
+            }",
+        );
+        assert_eq!(found.len(), 1);
+        assert!(matches!(found[0], SimpleModelError::InvalidValue { .. }));
+
+        let mut model = SimpleModel::new("the model".to_string());
+        let mut schedule =
+            Schedule::new("too long".to_string(), ScheduleProfile::Daily(vec![1.; 25]));
+        assert!(model.add_schedule(schedule.clone()).is_err());
+        schedule.profile = ScheduleProfile::Daily(vec![1.; 24]);
+        assert!(model.add_schedule(schedule).is_ok());
+        assert_eq!(model.schedules.len(), 1);
+    }
+}
@@ -17,22 +17,239 @@ LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
 OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
+use crate::model::SimpleModel;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
 use crate::Float;
+use derive::{ObjectAPI, ObjectIO};
+use std::rc::Rc;
 
-enum ShadingLocation {
+/// The side of a [`Fenestration`](crate::Fenestration) in which
+/// a [`ShadingDevice`] is placed
+#[derive(Copy, Clone, Eq, PartialEq, ObjectIO)]
+pub enum ShadingLocation {
+    /// Facing the interior of the building
     Interior,
-    Exterior
+
+    /// Facing the exterior of the building
+    Exterior,
 }
 
-enum ShadingClass {
+/// The kind of [`ShadingDevice`]
+#[derive(Copy, Clone, Eq, PartialEq, ObjectIO)]
+pub enum ShadingClass {
+    /// Blinds with horizontal slats (e.g., venetian blinds)
     HorizontalBlinds,
+
+    /// Blinds with vertical slats
     VerticalBlinds,
-    Curtain
+
+    /// A curtain or a roller shade
+    Curtain,
+
+    /// A fixed horizontal projection above a fenestration
+    Overhang,
 }
 
+/// A device that shades one or more [`Fenestration`](crate::Fenestration)s.
+///
+/// Blinds and curtains can be deployed and retracted, so they have a
+/// `deployment` (i.e., the fraction of the fenestration they cover, from
+/// 0 to 1) that can be read and changed by control algorithms.
+/// Overhangs are fixed, so they have none.
+///
+/// ### Example
+///
+/// ```rs
+/// ShadingDevice {
+///     name: "Kitchen blinds",
+///     location: ShadingLocation::Interior,
+///     class: ShadingClass::HorizontalBlinds,
+///     solar_transmittance: 0.1,
+///     solar_reflectance: 0.6
+/// }
+///
+/// Fenestration {
+///     name: "Kitchen window",
+///     shading: "Kitchen blinds",
+///     // ...
+/// }
+/// ```
+#[derive(ObjectIO, ObjectAPI, Clone)]
+#[api_methods(is_operable)]
 pub struct ShadingDevice {
-    location: ShadingLocation,
-    class: ShadingClass
+    /// The name of the shading device
+    pub name: String,
+
     /// The position of this object in its contaner Vector
-    index: usize,
-}
\ No newline at end of file
+    index: Option<usize>,
+
+    /// Whether the device is placed in the interior
+    /// or the exterior of the fenestrations it shades
+    pub location: ShadingLocation,
+
+    /// The kind of shading device
+    pub class: ShadingClass,
+
+    /// The solar transmittance of the device when
+    /// fully deployed (from 0 to 1)
+    solar_transmittance: Option<Float>,
+
+    /// The solar reflectance of the device (from 0 to 1)
+    solar_reflectance: Option<Float>,
+
+    /// The visible transmittance of the device when
+    /// fully deployed (from 0 to 1)
+    visible_transmittance: Option<Float>,
+
+    /// The thermal absorbtance (i.e., emissivity) of
+    /// the device (from 0 to 1)
+    thermal_absorbtance: Option<Float>,
+
+    /// The depth of an overhang (i.e., how far it
+    /// projects from the fenestration), in m
+    depth: Option<Float>,
+
+    /// The fraction of the fenestration that is
+    /// covered by the device (from 0 to 1)
+    #[operational]
+    deployment: StateElementField,
+}
+
+impl ShadingDevice {
+    /// Checks whether the device can be deployed and
+    /// retracted (i.e., it is not an overhang)
+    pub fn is_operable(&self) -> bool {
+        match self.class {
+            ShadingClass::HorizontalBlinds
+            | ShadingClass::VerticalBlinds
+            | ShadingClass::Curtain => true,
+            ShadingClass::Overhang => false,
+        }
+    }
+
+    /// Pushes the operational states of the [`ShadingDevice`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// Nothing is pushed if the `ShadingDevice` is not operable
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("ShadingDevice has not been added to the model");
+        if self.is_operable() {
+            let state_index = state.push(
+                SimulationStateElement::ShadingDeviceDeployment(obj_index),
+                0.,
+            );
+            self.set_deployment_index(state_index);
+        }
+    }
+}
+
+impl SimpleModel {
+    /// Adds a [`ShadingDevice`] to the [`SimpleModel`]
+    pub fn add_shading_device(
+        &mut self,
+        mut add: ShadingDevice,
+        state: &mut SimulationStateHeader,
+    ) -> Rc<ShadingDevice> {
+        // Check the index of this object
+        let obj_index = self.shading_devices.len();
+        add.set_index(obj_index);
+
+        // Push the deployment state, and map into the object
+        add.push_operational_states(state);
+
+        // Add to model, and return a reference
+        let add = Rc::new(add);
+        self.shading_devices.push(Rc::clone(&add));
+        add
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_shading_device() {
+        let src = b"
+Substance::Normal { name: \"glass\" }
+
+Material {
+    name: \"glass layer\",
+    substance: \"glass\",
+    thickness: 0.004
+}
+
+Construction {
+    name: \"the glazing\",
+    materials: [\"glass layer\"]
+}
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the glazing\",
+    operation_type: FenestrationPositions::FixedClosed(),
+    fenestration_type: FenestrationType::Window(),
+    shading: \"the blinds\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1]
+}
+
+ShadingDevice {
+    name: \"the blinds\",
+    location: ShadingLocation::Interior(),
+    class: ShadingClass::HorizontalBlinds(),
+    solar_transmittance: 0.1,
+    solar_reflectance: 0.6
+}
+
+ShadingDevice {
+    name: \"the overhang\",
+    location: ShadingLocation::Exterior(),
+    class: ShadingClass::Overhang(),
+    depth: 0.5
+}
+";
+        let (model, mut header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+
+        assert_eq!(model.shading_devices.len(), 2);
+        let blinds = &model.shading_devices[0];
+        assert!(blinds.location == ShadingLocation::Interior);
+        assert!(blinds.class == ShadingClass::HorizontalBlinds);
+        assert!((blinds.solar_transmittance().unwrap() - 0.1).abs() < 1e-6);
+        assert!(blinds.is_operable());
+        assert!(blinds.depth().is_err());
+
+        let overhang = &model.shading_devices[1];
+        assert!(!overhang.is_operable());
+        assert!(overhang.deployment_index().is_none());
+        assert!((overhang.depth().unwrap() - 0.5).abs() < 1e-6);
+
+        // The window is shaded by the blinds
+        let shading = model.fenestrations[0].shading().unwrap();
+        assert!(Rc::ptr_eq(shading, blinds));
+
+        // Blinds can be deployed
+        let mut state = header.take_values().unwrap();
+        assert_eq!(blinds.deployment(&state), Some(0.0));
+        blinds.set_deployment(&mut state, 0.5);
+        assert_eq!(blinds.deployment(&state), Some(0.5));
+        let i = blinds.deployment_index().unwrap();
+        assert_eq!(
+            header.elements[i],
+            SimulationStateElement::ShadingDeviceDeployment(0)
+        );
+
+        // And written back
+        let spl = model.to_spl();
+        let (read_back, _) = SimpleScanner::new(spl.as_bytes(), 1).parse_model().unwrap();
+        assert_eq!(read_back.shading_devices.len(), 2);
+        assert!(read_back.fenestrations[0].shading().is_ok());
+    }
+}
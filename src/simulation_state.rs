@@ -19,7 +19,9 @@ SOFTWARE.
 */
 use crate::Float;
 
+use crate::model::SimpleModel;
 use crate::simulation_state_element::SimulationStateElement;
+use std::collections::HashMap;
 use std::ops::Index;
 
 pub type SimulationState = Vec<Float>;
@@ -29,7 +31,8 @@ pub type SimulationState = Vec<Float>;
 ///
 /// To make it quicker to operate, the following conventions
 /// are enforced:
-/// * Personal elements go first, then Operational go second, and Physical go third. (This is checked when pushing elements to the state)
+/// * Personal elements go first, then Operational go second, and Physical go third. (Elements
+///   can be pushed in any order, and [`SimulationStateHeader::finalize`] sorts them)
 /// * Elements cannot be repeated (this is not really checked.).
 pub struct SimulationStateHeader {
     /// The number of operational StateElements
@@ -40,27 +43,28 @@ pub struct SimulationStateHeader {
     /// in the State
     n_individual: usize,
 
-    /// A flag indicating whether any physical
-    /// element has been assigned. If it has, then
-    /// no more individual elements are allowed. This
-    /// allows maintaining (this is a convention, made in favour
-    /// of performance) all the individual elements at the
-    /// beginning, then all the operational ones, and then
-    /// all the physical ones
+    /// A flag indicating whether any physical element has been
+    /// pushed. If it has, pushing an individual or operational
+    /// element clears the `sorted` flag.
     passed_operational: bool,
 
-    /// A flag indicating whether a non-individual
-    /// element has been assigned. If it has, then
-    /// no more individual elements are allowed. This
-    /// allows maintaining (this is a convention, made in favour
-    /// of performance) all the individual elements at the
-    /// beginning, then all the operational ones, and then
-    /// all the physical ones
+    /// A flag indicating whether any non-individual element has
+    /// been pushed. If it has, pushing an individual element
+    /// clears the `sorted` flag.
     passed_personal: bool,
 
-    /// The elements in the SimulationState
+    /// A flag indicating whether the elements are sorted by
+    /// category (i.e., personal, operational and physical). Pushing an
+    /// element out of order clears it, and `finalize()` sets it again.
+    sorted: bool,
+
+    /// The elements in the SimulationState. They should be
+    /// added by calling `push()`, so the header can find them.
     pub elements: Vec<SimulationStateElement>,
 
+    /// The position of each element within `elements`
+    positions: HashMap<SimulationStateElement, usize>,
+
     /// The default values, from which the simulation will start
     pub default_values: Option<SimulationState>,
 }
@@ -72,7 +76,9 @@ impl std::default::Default for SimulationStateHeader {
             n_individual: 0,
             passed_operational: false,
             passed_personal: false,
+            sorted: true,
             elements: Vec::new(),
+            positions: HashMap::new(),
             default_values: Some(Vec::new()),
         }
     }
@@ -87,23 +93,26 @@ impl SimulationStateHeader {
     /// Pushes an SimulationStateElement into the elements
     /// vector.
     ///
-    /// It ensures that operational elements are there first,
-    /// and that physical are there afterwards.
+    /// Elements can be pushed in any order. If an element arrives after
+    /// others of a later category (e.g., an operational one after a
+    /// physical one), the header is marked as not sorted, and
+    /// [`SimulationStateHeader::finalize`] needs to be called before using it.
     ///
-    /// Returns the index of the new element.
+    /// Returns the index of the new element (which can change when
+    /// finalizing).
     pub fn push(&mut self, e: SimulationStateElement, value: Float) -> usize {
         if e.is_personal() {
-            // If individual, check if we are passed that... if not, count
-            // and add
+            // If individual, check if we are passed that... if so, the
+            // header needs sorting
             if self.passed_personal {
-                panic!("Trying to add an individual SimulationStateElement '{:?}' after no more were allowed", e);
+                self.sorted = false;
             }
             self.n_individual += 1;
         } else if e.is_operational() {
-            // If operational, check if we are passed that... if not, count
-            // and add... mark individuals as passed
+            // If operational, check if we are passed that... if so, the
+            // header needs sorting... mark individuals as passed
             if self.passed_operational {
-                panic!("Trying to add an operational SimulationStateElement '{:?}' after no more were allowed", e);
+                self.sorted = false;
             }
             self.passed_personal = true;
             self.n_operational += 1;
@@ -116,6 +125,7 @@ impl SimulationStateHeader {
 
         let ret = self.elements.len();
         self.elements.push(e);
+        self.positions.entry(e).or_insert(ret);
         if let Some(values) = &mut self.default_values {
             values.push(value);
         } else {
@@ -125,6 +135,119 @@ impl SimulationStateHeader {
         ret
     }
 
+    /// Checks whether the elements are sorted by category (i.e.,
+    /// personal, operational and physical). If they are not,
+    /// [`SimulationStateHeader::finalize`] needs to be called.
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
+    }
+
+    /// Sorts the elements by category (personal, then operational,
+    /// then physical), keeping the order in which they were pushed within
+    /// each category. The `default_values` (if they have not been taken) are
+    /// sorted as well, and the indices held by the objects in the
+    /// [`SimpleModel`] are updated so they keep pointing at their elements.
+    ///
+    /// Returns the new position of each element (i.e., the element that
+    /// was at `i` is now at `ret[i]`).
+    pub fn finalize(&mut self, model: &SimpleModel) -> Vec<usize> {
+        let category = |e: &SimulationStateElement| {
+            if e.is_personal() {
+                0
+            } else if e.is_operational() {
+                1
+            } else {
+                2
+            }
+        };
+        let mut order: Vec<usize> = (0..self.elements.len()).collect();
+        // This sort is stable, so the order within categories is kept
+        order.sort_by_key(|i| category(&self.elements[*i]));
+
+        let mut new_positions = vec![0; order.len()];
+        for (new, old) in order.iter().enumerate() {
+            new_positions[*old] = new;
+        }
+
+        self.elements = order.iter().map(|i| self.elements[*i]).collect();
+        if let Some(values) = &mut self.default_values {
+            *values = order.iter().map(|i| values[*i]).collect();
+        }
+        let n = self.elements.len();
+        self.passed_personal = self.n_individual < n;
+        self.passed_operational = self.n_individual + self.n_operational < n;
+        self.sorted = true;
+        self.positions.clear();
+        for (i, e) in self.elements.iter().enumerate() {
+            self.positions.entry(*e).or_insert(i);
+        }
+
+        if order.iter().enumerate().any(|(new, old)| new != *old) {
+            model.map_state_indices(&|i| new_positions.get(i).copied());
+        }
+        new_positions
+    }
+
+    /// Panics if the elements are not sorted by category, as the
+    /// ranges of each category would be meaningless
+    fn assert_sorted(&self) {
+        assert!(
+            self.sorted,
+            "The SimulationStateHeader is not sorted by category... call finalize() first"
+        );
+    }
+
+    /// Finds the position of a [`SimulationStateElement`] within
+    /// the header (and therefore within the [`SimulationState`])
+    pub fn find(&self, e: &SimulationStateElement) -> Option<usize> {
+        self.positions.get(e).copied()
+    }
+
+    /// Iterates over the personal elements, and their positions
+    pub fn iter_personal(&self) -> impl Iterator<Item = (usize, &SimulationStateElement)> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_personal())
+    }
+
+    /// Iterates over the operational elements, and their positions
+    pub fn iter_operational(&self) -> impl Iterator<Item = (usize, &SimulationStateElement)> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_operational())
+    }
+
+    /// Iterates over the physical elements, and their positions
+    pub fn iter_physical(&self) -> impl Iterator<Item = (usize, &SimulationStateElement)> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.is_physical())
+    }
+
+    /// Iterates over the elements that belong to an object, and their
+    /// positions. The object is identified by its type, as written
+    /// in the input files (e.g., `"Surface"`), and its index
+    /// within the [`SimpleModel`].
+    pub fn iter_object<'a>(
+        &'a self,
+        object_type: &'a str,
+        index: usize,
+    ) -> impl Iterator<Item = (usize, &'a SimulationStateElement)> {
+        self.elements
+            .iter()
+            .enumerate()
+            .filter(move |(_, e)| e.object_type() == object_type && e.object_index() == index)
+    }
+
+    /// Builds human-readable labels for all the elements, using the
+    /// names of the objects in the [`SimpleModel`] (see [`SimulationStateElement::label`])
+    pub fn labels(&self, model: &SimpleModel) -> Vec<String> {
+        self.elements.iter().map(|e| e.label(model)).collect()
+    }
+
     /// Returns the number of elements in the
     /// State element
     pub fn len(&self) -> usize {
@@ -157,11 +280,16 @@ impl SimulationStateHeader {
     /// Copies the Physical SimulationStateElements objects from `origin` to `destination`.
     /// It is expected that all the Operational BuildingStateElements are bundled at
     /// the beginning of the element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is not sorted (see [`SimulationStateHeader::finalize`])
     pub fn copy_physical_state(
         &mut self,
         origin: &SimulationState,
         destination: &mut SimulationState,
     ) {
+        self.assert_sorted();
         debug_assert_eq!(origin.len(), self.len());
         debug_assert_eq!(origin.len(), destination.len());
 
@@ -180,12 +308,17 @@ impl SimulationStateHeader {
 
     /// Copies the Operational SimulationStateElements objects from origin to destination.
     /// It is expected that all the Operational BuildingStateElements are bundled at
-    /// the beginning of the element.    
+    /// the beginning of the element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is not sorted (see [`SimulationStateHeader::finalize`])
     pub fn copy_operational_state(
         &mut self,
         origin: &SimulationState,
         destination: &mut SimulationState,
     ) {
+        self.assert_sorted();
         debug_assert_eq!(origin.len(), self.len());
         debug_assert_eq!(origin.len(), destination.len());
 
@@ -205,11 +338,16 @@ impl SimulationStateHeader {
     /// Copies the Individual SimulationStateElements objects from origin to destination.
     /// It is expected that all the Operational BuildingStateElements are bundled at
     /// the beginning of the element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is not sorted (see [`SimulationStateHeader::finalize`])
     pub fn copy_individual_state(
         &mut self,
         origin: &SimulationState,
         destination: &mut SimulationState,
     ) {
+        self.assert_sorted();
         debug_assert_eq!(origin.len(), self.len());
         debug_assert_eq!(origin.len(), destination.len());
 
@@ -227,7 +365,12 @@ impl SimulationStateHeader {
 
     /// Takes the `default_values` from the [`SimulationStateHeader`]. Leaves
     /// `None` in its place.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header is not sorted (see [`SimulationStateHeader::finalize`])
     pub fn take_values(&mut self) -> Option<SimulationState> {
+        self.assert_sorted();
         self.default_values.take()
     }
 }
@@ -240,6 +383,91 @@ impl Index<usize> for SimulationStateHeader {
     }
 }
 
+impl SimpleModel {
+    /// Builds a new [`SimulationStateHeader`] with the state elements of
+    /// all the objects in the [`SimpleModel`]—with their default values—and
+    /// maps them into the objects, replacing any index they had before.
+    ///
+    /// Elements are pushed by category (personal, then operational, then
+    /// physical) and, within each category, by type of object and in the
+    /// order in which objects are stored in the model. So, the same model
+    /// always produces the same layout.
+    pub fn build_state_header(&self) -> SimulationStateHeader {
+        // New types of objects with state elements need to be added here
+        // and in map_state_indices() (test_build_state_header_coverage checks it)
+        self.map_state_indices(&|_| None);
+        let mut state = SimulationStateHeader::new();
+
+        // Personal
+        for occupant in &self.occupants {
+            occupant.push_personal_states(&mut state);
+        }
+
+        // Operational
+        for fenestration in &self.fenestrations {
+            fenestration.push_operational_states(&mut state);
+        }
+        for shading_device in &self.shading_devices {
+            shading_device.push_operational_states(&mut state);
+        }
+        for thermostat in &self.thermostats {
+            thermostat.push_operational_states(&mut state);
+        }
+        for hvac in &self.hvacs {
+            hvac.push_operational_states(&mut state);
+        }
+        for luminaire in &self.luminaires {
+            luminaire.push_operational_states(&mut state);
+        }
+        for equipment in &self.equipment {
+            equipment.push_operational_states(&mut state);
+        }
+        for ventilation in &self.ventilations {
+            ventilation.push_operational_states(&mut state);
+        }
+
+        // Physical
+        for space in &self.spaces {
+            space.push_physical_states(&mut state);
+        }
+        for surface in &self.surfaces {
+            surface.push_physical_states(&mut state);
+        }
+        for fenestration in &self.fenestrations {
+            fenestration.push_physical_states(&mut state);
+        }
+        for air_exchange in &self.air_exchanges {
+            air_exchange.push_physical_states(&mut state);
+        }
+
+        state
+    }
+
+    /// Changes the state indices of all the objects in the model,
+    /// using `f` (see the `map_state_indices()` method of each object)
+    pub(crate) fn map_state_indices(&self, f: &dyn Fn(usize) -> Option<usize>) {
+        self.occupants.iter().for_each(|o| o.map_state_indices(f));
+        self.fenestrations
+            .iter()
+            .for_each(|o| o.map_state_indices(f));
+        self.shading_devices
+            .iter()
+            .for_each(|o| o.map_state_indices(f));
+        self.thermostats.iter().for_each(|o| o.map_state_indices(f));
+        self.hvacs.iter().for_each(|o| o.map_state_indices(f));
+        self.luminaires.iter().for_each(|o| o.map_state_indices(f));
+        self.equipment.iter().for_each(|o| o.map_state_indices(f));
+        self.ventilations
+            .iter()
+            .for_each(|o| o.map_state_indices(f));
+        self.spaces.iter().for_each(|o| o.map_state_indices(f));
+        self.surfaces.iter().for_each(|o| o.map_state_indices(f));
+        self.air_exchanges
+            .iter()
+            .for_each(|o| o.map_state_indices(f));
+    }
+}
+
 /***********/
 /* TESTING */
 /***********/
@@ -307,8 +535,8 @@ mod testing {
     }
 
     #[test]
-    #[should_panic]
-    fn push_panic() {
+    fn test_push_unsorted() {
+        let model = SimpleModel::new("the model".to_string());
         let mut state = SimulationStateHeader::new();
 
         // Add one operational
@@ -316,9 +544,86 @@ mod testing {
 
         // push a physical one
         state.push(SimulationStateElement::SpaceDryBulbTemperature(2), 2.);
+        assert!(state.is_sorted());
+
+        // Add an operational and a personal one... not sorted anymore
+        state.push(SimulationStateElement::LuminairePowerConsumption(1), 3.0);
+        state.push(SimulationStateElement::Clothing(0), 4.0);
+        assert!(!state.is_sorted());
 
-        // Add an operational... it should panic now
+        let new_positions = state.finalize(&model);
+        assert!(state.is_sorted());
+        assert_eq!(new_positions, vec![1, 3, 2, 0]);
+        assert_eq!(
+            state.elements,
+            vec![
+                SimulationStateElement::Clothing(0),
+                SimulationStateElement::LuminairePowerConsumption(0),
+                SimulationStateElement::LuminairePowerConsumption(1),
+                SimulationStateElement::SpaceDryBulbTemperature(2),
+            ]
+        );
+        assert_eq!(state.n_individual(), 1);
+        assert_eq!(state.n_operational(), 2);
+        assert_eq!(state.take_values().unwrap(), vec![4.0, 1.0, 3.0, 2.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "call finalize() first")]
+    fn test_take_values_unsorted() {
+        let mut state = SimulationStateHeader::new();
+        state.push(SimulationStateElement::SpaceDryBulbTemperature(0), 2.);
+        state.push(SimulationStateElement::LuminairePowerConsumption(0), 1.0);
+        state.take_values();
+    }
+
+    #[test]
+    #[should_panic(expected = "call finalize() first")]
+    fn test_copy_unsorted() {
+        let mut state = SimulationStateHeader::new();
+        state.push(SimulationStateElement::SpaceDryBulbTemperature(0), 2.);
         state.push(SimulationStateElement::LuminairePowerConsumption(0), 1.0);
+        let origin = vec![1., 2.];
+        let mut destination = vec![0., 0.];
+        state.copy_operational_state(&origin, &mut destination);
+    }
+
+    #[test]
+    fn test_finalize_remaps_objects() {
+        use crate::{Luminaire, Occupant, Space};
+        use std::rc::Rc;
+
+        let mut model = SimpleModel::new("the model".to_string());
+        let mut header = SimulationStateHeader::new();
+
+        // Physical elements first
+        let space = model.add_space(Space::new("the space".to_string()));
+        space.push_physical_states(&mut header);
+
+        // Then operational and personal ones
+        let luminaire = Luminaire::new("the luminaire".to_string());
+        let luminaire = model.add_luminaire(luminaire, &mut header);
+        let occupant = Occupant::new("the occupant".to_string(), Rc::clone(&space));
+        let occupant = model.add_occupant(occupant, &mut header);
+        assert!(!header.is_sorted());
+
+        header.finalize(&model);
+        assert!(header.is_sorted());
+        let state = header.take_values().unwrap();
+        assert_eq!(
+            header[occupant.location_index().unwrap()],
+            SimulationStateElement::OccupantLocation(0)
+        );
+        assert_eq!(occupant.location(&state), Some(0.));
+        assert_eq!(
+            header[luminaire.power_consumption_index().unwrap()],
+            SimulationStateElement::LuminairePowerConsumption(0)
+        );
+        assert_eq!(
+            header[space.dry_bulb_temperature_index().unwrap()],
+            SimulationStateElement::SpaceDryBulbTemperature(0)
+        );
+        assert_eq!(space.dry_bulb_temperature(&state), Some(22.));
     }
 
     #[test]
@@ -327,8 +632,8 @@ mod testing {
         let mut headers = SimulationStateHeader::new();
 
         // Add individual ones
-        headers.push(SimulationStateElement::Clothing, 0.0);
-        headers.push(SimulationStateElement::Clothing, 1.0);
+        headers.push(SimulationStateElement::Clothing(0), 0.0);
+        headers.push(SimulationStateElement::Clothing(1), 1.0);
 
         // Add operational ones
         headers.push(SimulationStateElement::LuminairePowerConsumption(1), 2.0);
@@ -364,8 +669,8 @@ mod testing {
         let mut headers = SimulationStateHeader::new();
 
         // Add individual ones
-        headers.push(SimulationStateElement::Clothing, 0.0);
-        headers.push(SimulationStateElement::Clothing, 1.0);
+        headers.push(SimulationStateElement::Clothing(0), 0.0);
+        headers.push(SimulationStateElement::Clothing(1), 1.0);
 
         // Add operational ones
         headers.push(SimulationStateElement::LuminairePowerConsumption(1), 2.0);
@@ -401,8 +706,8 @@ mod testing {
         let mut headers = SimulationStateHeader::new();
 
         // Add individual ones
-        headers.push(SimulationStateElement::Clothing, 0.0);
-        headers.push(SimulationStateElement::Clothing, 1.0);
+        headers.push(SimulationStateElement::Clothing(0), 0.0);
+        headers.push(SimulationStateElement::Clothing(1), 1.0);
 
         // Add operational ones
         headers.push(SimulationStateElement::LuminairePowerConsumption(1), 2.0);
@@ -431,4 +736,230 @@ mod testing {
         assert_eq!(state2[4], 4.);
         assert_eq!(state2[5], 5.);
     }
+
+    #[test]
+    fn test_build_state_header() {
+        let src = b"
+Space { name: \"kitchen\" }
+Space { name: \"living room\" }
+
+Substance::Normal { name: \"the substance\" }
+Material { name: \"the material\", substance: \"the substance\", thickness: 0.1 }
+Construction { name: \"the construction\", materials: [\"the material\"] }
+
+Surface {
+    name: \"the wall\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1]
+}
+
+Fenestration {
+    name: \"the door\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::Continuous(),
+    fenestration_type: FenestrationType::Door(),
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 2, 0, 0, 2]
+}
+
+AirExchange {
+    name: \"the exchange\",
+    from_space: \"kitchen\",
+    to_space: \"living room\"
+}
+
+Luminaire { name: \"the luminaire\", target_space: \"kitchen\" }
+
+Occupant { name: \"the family\", space: \"living room\", count: 4 }
+";
+        let (model, parsed_header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+
+        // Parsing gives the same layout
+        let mut header = model.build_state_header();
+        assert_eq!(header.elements, parsed_header.elements);
+        assert_eq!(header.n_individual(), 3);
+        assert_eq!(header.n_operational(), 2);
+        for (i, e) in header.elements.iter().enumerate() {
+            if i < 3 {
+                assert!(e.is_personal());
+            } else if i < 5 {
+                assert!(e.is_operational());
+            } else {
+                assert!(e.is_physical());
+            }
+        }
+
+        // The indices point to the new header
+        let space = &model.spaces[1];
+        let i = space.dry_bulb_temperature_index().unwrap();
+        assert_eq!(
+            header[i],
+            SimulationStateElement::SpaceDryBulbTemperature(1)
+        );
+        let i = model.surfaces[0].back_ir_irradiance_index().unwrap();
+        assert_eq!(
+            header[i],
+            SimulationStateElement::SurfaceBackIRIrradiance(0)
+        );
+        let i = model.luminaires[0].power_consumption_index().unwrap();
+        assert_eq!(
+            header[i],
+            SimulationStateElement::LuminairePowerConsumption(0)
+        );
+        let i = model.air_exchanges[0].volume_index().unwrap();
+        assert_eq!(
+            header[i],
+            SimulationStateElement::SpaceAirExchangeVolume(0, 1)
+        );
+
+        // And the defaults are there
+        let state = header.take_values().unwrap();
+        assert_eq!(model.occupants[0].location(&state), Some(1.));
+        assert_eq!(space.dry_bulb_temperature(&state), Some(22.));
+
+        // Same model, same layout
+        let other = model.build_state_header();
+        assert_eq!(header.elements, other.elements);
+    }
+
+    #[test]
+    fn test_build_state_header_coverage() {
+        // One object of each type that has state elements
+        let src = b"
+Space { name: \"kitchen\" }
+Space { name: \"living room\" }
+
+Substance::Normal { name: \"the substance\" }
+Material { name: \"the material\", substance: \"the substance\", thickness: 0.1 }
+Construction { name: \"the construction\", materials: [\"the material\"] }
+
+Surface {
+    name: \"the wall\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1]
+}
+
+ShadingDevice {
+    name: \"the blinds\",
+    location: ShadingLocation::Interior(),
+    class: ShadingClass::HorizontalBlinds()
+}
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::Continuous(),
+    fenestration_type: FenestrationType::Window(),
+    shading: \"the blinds\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 2, 0, 0, 2]
+}
+
+Thermostat { name: \"the thermostat\", target_spaces: [\"kitchen\"], heating_setpoint: 20, cooling_setpoint: 24 }
+HVAC::IdealHeaterCooler { name: \"the hvac\", target_spaces: [\"kitchen\"] }
+HVAC::AirSourceHeatPump {
+    name: \"the heat pump\",
+    target_space: \"living room\",
+    heating_cop_coefficients: [3.0, 0.05],
+    cooling_cop_coefficients: [5.5, -0.08, 0.001]
+}
+Luminaire { name: \"the luminaire\", target_space: \"kitchen\" }
+Equipment { name: \"the stove\", fuel: EquipmentFuel::Gas(), max_power: 2000, target_space: \"kitchen\" }
+Ventilation { name: \"the ventilation\", target_spaces: [\"kitchen\"] }
+AirExchange { name: \"the exchange\", from_space: \"kitchen\", to_space: \"living room\" }
+Occupant { name: \"the family\", space: \"living room\", count: 4 }
+";
+        let (model, header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+
+        // Every type of element is registered, except for the ones
+        // that depend on the simulation (e.g., the nodes of the walls)
+        for variant in SimulationStateElement::variant_names() {
+            let e = SimulationStateElement::from_variant(variant, &[0])
+                .or_else(|| SimulationStateElement::from_variant(variant, &[0, 1]))
+                .unwrap();
+            let registered = header.elements.iter().any(|h| h.variant_name() == *variant);
+            if matches!(
+                e,
+                SimulationStateElement::SurfaceNodeTemperature(..)
+                    | SimulationStateElement::FenestrationNodeTemperature(..)
+            ) {
+                assert!(!registered, "{:?} should not be registered", e);
+            } else {
+                assert!(registered, "{:?} is not registered", e);
+            }
+        }
+
+        // And every element is held by one object in the model
+        let held = std::cell::Cell::new(0);
+        model.map_state_indices(&|i| {
+            held.set(held.get() + 1);
+            Some(i)
+        });
+        assert_eq!(held.get(), header.len());
+    }
+
+    #[test]
+    fn test_find_and_labels() {
+        let src = b"
+Space { name: \"Bedroom\" }
+Space { name: \"Kitchen\" }
+
+Substance::Normal { name: \"the substance\" }
+Material { name: \"the material\", substance: \"the substance\", thickness: 0.1 }
+Construction { name: \"the construction\", materials: [\"the material\"] }
+
+Surface {
+    name: \"the wall\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 0, 1, 0, 0, 1]
+}
+
+AirExchange { name: \"the exchange\", from_space: \"Kitchen\", to_space: \"Bedroom\" }
+Luminaire { name: \"the lamp\", target_space: \"Bedroom\" }
+";
+        let (model, _) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let header = model.build_state_header();
+
+        // Reverse index
+        let i = header
+            .find(&SimulationStateElement::SpaceDryBulbTemperature(0))
+            .unwrap();
+        assert_eq!(Some(i), model.spaces[0].dry_bulb_temperature_index());
+        assert!(header
+            .find(&SimulationStateElement::SpaceDryBulbTemperature(9))
+            .is_none());
+
+        // Iterators
+        assert_eq!(header.iter_personal().count(), header.n_individual());
+        assert_eq!(header.iter_operational().count(), header.n_operational());
+        assert_eq!(
+            header.iter_physical().count(),
+            header.len() - header.n_operational() - header.n_individual()
+        );
+        assert_eq!(header.iter_object("Surface", 0).count(), 8);
+        for (i, e) in header.iter_object("Space", 1) {
+            assert_eq!(header[i], *e);
+            assert_eq!(e.object_index(), 1);
+        }
+
+        // Labels
+        let labels = header.labels(&model);
+        assert_eq!(labels[i], "Bedroom: dry bulb temperature");
+        let i = header
+            .find(&SimulationStateElement::LuminairePowerConsumption(0))
+            .unwrap();
+        assert_eq!(labels[i], "the lamp: power consumption");
+        let i = header
+            .find(&SimulationStateElement::SpaceAirExchangeVolume(1, 0))
+            .unwrap();
+        assert_eq!(labels[i], "Kitchen: air exchange volume to Bedroom");
+        assert_eq!(
+            SimulationStateElement::SurfaceNodeTemperature(3, 1).label(&model),
+            "Surface #3: node 1 temperature"
+        );
+    }
 }
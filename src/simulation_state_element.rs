@@ -18,19 +18,30 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+use crate::model::SimpleModel;
 use derive::StateElements;
 use std::cell::RefCell;
 
 pub type StateElementField = RefCell<Option<usize>>;
 
 /// The idea is to have a cheap-to-clone (or copy?) structure
-#[derive(Debug, Copy, Clone, PartialEq, StateElements)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, StateElements)]
 pub enum SimulationStateElement {
     /* PERSONAL ELEMENTS */
-    /// The amount of clothing the person is using,
-    /// in Clo value
+    /// The amount of clothing the occupants are using,
+    /// in Clo value (occupant index)
     #[personal]
-    Clothing,
+    Clothing(usize),
+
+    /// The metabolic rate of the occupants, in met
+    /// (occupant index)
+    #[personal]
+    ActivityLevel(usize),
+
+    /// The index of the Space in which the occupants
+    /// are (occupant index)
+    #[personal]
+    OccupantLocation(usize),
 
     /* OPERATION AND OCCUPATION */
     /// Represents how open is a fenestration.
@@ -46,11 +57,46 @@ pub enum SimulationStateElement {
     #[operational]
     HeatingCoolingPowerConsumption(usize),
 
+    /// The heat delivered by a Heating/Cooling system, in Watts.
+    /// It is negative when cooling (HVAC index)
+    #[operational]
+    HVACDeliveredHeat(usize),
+
+    /// The fraction of its max power at which a Heating/Cooling
+    /// system is working, from 0 to 1 (HVAC index)
+    #[operational]
+    HVACPartLoadRatio(usize),
+
     /// Represents the power being consumed by
     /// a Luminaire object, in Watts (luminaire index, power)
     #[operational]
     LuminairePowerConsumption(usize),
 
+    /// Represents the power being consumed by
+    /// an Equipment object, in Watts (equipment index)
+    #[operational]
+    EquipmentPowerConsumption(usize),
+
+    /// The heating setpoint of a Thermostat, in C
+    /// (thermostat index)
+    #[operational]
+    ThermostatHeatingSetpoint(usize),
+
+    /// The cooling setpoint of a Thermostat, in C
+    /// (thermostat index)
+    #[operational]
+    ThermostatCoolingSetpoint(usize),
+
+    /// The volume of air being supplied by a Ventilation
+    /// object, in m3/s (ventilation index)
+    #[operational]
+    VentilationFlowRate(usize),
+
+    /// Represents the fraction of its fenestrations that is covered
+    /// by a ShadingDevice, from 0 to 1 (shading device index)
+    #[operational]
+    ShadingDeviceDeployment(usize),
+
     /* SOLAR */
     // Space
     //SpaceTotalSolarHeatGain(usize),
@@ -199,6 +245,158 @@ pub enum SimulationStateElement {
     // SpaceLoudness(usize),
 }
 
+impl SimulationStateElement {
+    /// Gets the type of object (e.g., `"Space"`) this element
+    /// belongs to, and its index within the [`SimpleModel`]
+    fn object(&self) -> (&'static str, usize) {
+        match self {
+            Self::Clothing(i) | Self::ActivityLevel(i) | Self::OccupantLocation(i) => {
+                ("Occupant", *i)
+            }
+            Self::FenestrationOpenFraction(i)
+            | Self::FenestrationFrontConvectionCoefficient(i)
+            | Self::FenestrationBackConvectionCoefficient(i)
+            | Self::FenestrationFrontConvectiveHeatFlow(i)
+            | Self::FenestrationBackConvectiveHeatFlow(i)
+            | Self::FenestrationFrontSolarIrradiance(i)
+            | Self::FenestrationBackSolarIrradiance(i)
+            | Self::FenestrationFrontIRIrradiance(i)
+            | Self::FenestrationBackIRIrradiance(i)
+            | Self::FenestrationNodeTemperature(i, _) => ("Fenestration", *i),
+            Self::HeatingCoolingPowerConsumption(i)
+            | Self::HVACDeliveredHeat(i)
+            | Self::HVACPartLoadRatio(i) => ("HVAC", *i),
+            Self::LuminairePowerConsumption(i) => ("Luminaire", *i),
+            Self::EquipmentPowerConsumption(i) => ("Equipment", *i),
+            Self::ThermostatHeatingSetpoint(i) | Self::ThermostatCoolingSetpoint(i) => {
+                ("Thermostat", *i)
+            }
+            Self::VentilationFlowRate(i) => ("Ventilation", *i),
+            Self::ShadingDeviceDeployment(i) => ("ShadingDevice", *i),
+            Self::SpaceVentilationVolume(i)
+            | Self::SpaceVentilationTemperature(i)
+            | Self::SpaceDryBulbTemperature(i)
+            | Self::SpaceInfiltrationVolume(i)
+            | Self::SpaceInfiltrationTemperature(i)
+            | Self::SpaceAirExchangeVolume(i, _) => ("Space", *i),
+            Self::SurfaceFrontConvectionCoefficient(i)
+            | Self::SurfaceBackConvectionCoefficient(i)
+            | Self::SurfaceFrontConvectiveHeatFlow(i)
+            | Self::SurfaceBackConvectiveHeatFlow(i)
+            | Self::SurfaceFrontSolarIrradiance(i)
+            | Self::SurfaceBackSolarIrradiance(i)
+            | Self::SurfaceFrontIRIrradiance(i)
+            | Self::SurfaceBackIRIrradiance(i)
+            | Self::SurfaceNodeTemperature(i, _) => ("Surface", *i),
+        }
+    }
+
+    /// Gets the type of object this element belongs to, written
+    /// as in the input files (e.g., `"Space"` or `"ShadingDevice"`).
+    ///
+    /// Elements describing the air moving between two spaces
+    /// belong to the [`Space`](crate::Space) the air comes from.
+    pub fn object_type(&self) -> &'static str {
+        self.object().0
+    }
+
+    /// Gets the index of the object this element belongs to,
+    /// within its container in the [`SimpleModel`]
+    pub fn object_index(&self) -> usize {
+        self.object().1
+    }
+
+    /// Describes the variable represented by this element, without
+    /// referring to its object (e.g., `"dry bulb temperature"`)
+    pub fn description(&self) -> String {
+        match self {
+            Self::Clothing(_) => "clothing".to_string(),
+            Self::ActivityLevel(_) => "activity level".to_string(),
+            Self::OccupantLocation(_) => "location".to_string(),
+            Self::FenestrationOpenFraction(_) => "open fraction".to_string(),
+            Self::HeatingCoolingPowerConsumption(_)
+            | Self::LuminairePowerConsumption(_)
+            | Self::EquipmentPowerConsumption(_) => "power consumption".to_string(),
+            Self::HVACDeliveredHeat(_) => "delivered heat".to_string(),
+            Self::HVACPartLoadRatio(_) => "part load ratio".to_string(),
+            Self::ThermostatHeatingSetpoint(_) => "heating setpoint".to_string(),
+            Self::ThermostatCoolingSetpoint(_) => "cooling setpoint".to_string(),
+            Self::VentilationFlowRate(_) => "flow rate".to_string(),
+            Self::SpaceVentilationVolume(_) => "ventilation volume".to_string(),
+            Self::SpaceVentilationTemperature(_) => "ventilation temperature".to_string(),
+            Self::ShadingDeviceDeployment(_) => "deployment".to_string(),
+            Self::SurfaceFrontConvectionCoefficient(_)
+            | Self::FenestrationFrontConvectionCoefficient(_) => {
+                "front convection coefficient".to_string()
+            }
+            Self::SurfaceBackConvectionCoefficient(_)
+            | Self::FenestrationBackConvectionCoefficient(_) => {
+                "back convection coefficient".to_string()
+            }
+            Self::SurfaceFrontConvectiveHeatFlow(_)
+            | Self::FenestrationFrontConvectiveHeatFlow(_) => {
+                "front convective heat flow".to_string()
+            }
+            Self::SurfaceBackConvectiveHeatFlow(_)
+            | Self::FenestrationBackConvectiveHeatFlow(_) => {
+                "back convective heat flow".to_string()
+            }
+            Self::SurfaceFrontSolarIrradiance(_) | Self::FenestrationFrontSolarIrradiance(_) => {
+                "front solar irradiance".to_string()
+            }
+            Self::SurfaceBackSolarIrradiance(_) | Self::FenestrationBackSolarIrradiance(_) => {
+                "back solar irradiance".to_string()
+            }
+            Self::SurfaceFrontIRIrradiance(_) | Self::FenestrationFrontIRIrradiance(_) => {
+                "front IR irradiance".to_string()
+            }
+            Self::SurfaceBackIRIrradiance(_) | Self::FenestrationBackIRIrradiance(_) => {
+                "back IR irradiance".to_string()
+            }
+            Self::SpaceDryBulbTemperature(_) => "dry bulb temperature".to_string(),
+            Self::SpaceInfiltrationVolume(_) => "infiltration volume".to_string(),
+            Self::SpaceInfiltrationTemperature(_) => "infiltration temperature".to_string(),
+            Self::SpaceAirExchangeVolume(_, to) => format!("air exchange volume to space #{}", to),
+            Self::SurfaceNodeTemperature(_, node) | Self::FenestrationNodeTemperature(_, node) => {
+                format!("node {} temperature", node)
+            }
+        }
+    }
+
+    /// Builds a human-readable label for this element, using the names of
+    /// the objects in the [`SimpleModel`] (e.g., `"Bedroom: dry bulb temperature"`).
+    ///
+    /// Objects that are not in the model are referred to by
+    /// their type and index (e.g., `"Space #3: dry bulb temperature"`).
+    pub fn label(&self, model: &SimpleModel) -> String {
+        let name = |object_type: &str, i: usize| -> String {
+            let name = match object_type {
+                "Occupant" => model.occupants.get(i).map(|o| o.name().clone()),
+                "Fenestration" => model.fenestrations.get(i).map(|o| o.name().clone()),
+                "HVAC" => model.hvacs.get(i).map(|o| o.name().clone()),
+                "Luminaire" => model.luminaires.get(i).map(|o| o.name().clone()),
+                "Equipment" => model.equipment.get(i).map(|o| o.name().clone()),
+                "Thermostat" => model.thermostats.get(i).map(|o| o.name().clone()),
+                "Ventilation" => model.ventilations.get(i).map(|o| o.name().clone()),
+                "ShadingDevice" => model.shading_devices.get(i).map(|o| o.name().clone()),
+                "Space" => model.spaces.get(i).map(|o| o.name().clone()),
+                "Surface" => model.surfaces.get(i).map(|o| o.name().clone()),
+                _ => None,
+            };
+            name.unwrap_or_else(|| format!("{} #{}", object_type, i))
+        };
+
+        let (object_type, i) = self.object();
+        let description = match self {
+            Self::SpaceAirExchangeVolume(_, to) => {
+                format!("air exchange volume to {}", name("Space", *to))
+            }
+            _ => self.description(),
+        };
+        format!("{}: {}", name(object_type, i), description)
+    }
+}
+
 /***********/
 /* TESTING */
 /***********/
@@ -226,7 +424,7 @@ mod testing {
         assert!(!e.is_personal());
 
         // Individual
-        let e = SimulationStateElement::Clothing;
+        let e = SimulationStateElement::Clothing(2);
         assert!(!e.is_physical());
         assert!(!e.is_operational());
         assert!(e.is_personal());
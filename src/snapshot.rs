@@ -0,0 +1,390 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+// Compact binary snapshots of a [`SimulationStateHeader`] and the
+// values of a [`SimulationState`], so simulations can be restarted
+// from a checkpoint.
+//
+// All numbers are written in little-endian. A header is written as the
+// `HEADER_MAGIC`, the number of personal, operational and total elements
+// (`u64`), and then each element as the length (`u8`) and the name of its
+// variant (UTF-8), its number of fields (`u8`) and its fields (`u64`).
+// Variants are stored by name, so snapshots remain valid when variants
+// are added to—or reordered within—`SimulationStateElement`. A snapshot is a header
+// followed by the `STATE_MAGIC`, the size in bytes of each value (`u8`),
+// the number of values (`u64`) and the values (`Float`).
+
+use crate::simulation_state::{SimulationState, SimulationStateHeader};
+use crate::simulation_state_element::SimulationStateElement;
+use crate::{Float, SimpleModelError};
+
+/// Identifies the beginning of a serialized [`SimulationStateHeader`]
+const HEADER_MAGIC: &[u8; 4] = b"SSH2";
+
+/// Identifies the beginning of the serialized values of a [`SimulationState`]
+const STATE_MAGIC: &[u8; 4] = b"SSV1";
+
+/// The number of bytes used for storing each value of a [`SimulationState`]
+const FLOAT_SIZE: usize = std::mem::size_of::<Float>();
+
+/// Reads the bytes of a snapshot, in order
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], SimpleModelError> {
+        if self.position + n > self.bytes.len() {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!("unexpected end of data at byte {}", self.position),
+            });
+        }
+        let ret = &self.bytes[self.position..self.position + n];
+        self.position += n;
+        Ok(ret)
+    }
+
+    fn magic(&mut self, magic: &[u8; 4]) -> Result<(), SimpleModelError> {
+        let position = self.position;
+        if self.take(4)? != magic {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!(
+                    "expecting '{}' at byte {}",
+                    String::from_utf8_lossy(magic),
+                    position
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn u8(&mut self) -> Result<u8, SimpleModelError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn name(&mut self) -> Result<&'a str, SimpleModelError> {
+        let position = self.position;
+        let len = self.u8()? as usize;
+        std::str::from_utf8(self.take(len)?).map_err(|_| SimpleModelError::InvalidSnapshot {
+            message: format!("invalid variant name at byte {}", position),
+        })
+    }
+
+    fn usize(&mut self) -> Result<usize, SimpleModelError> {
+        let mut b = [0; 8];
+        b.copy_from_slice(self.take(8)?);
+        Ok(u64::from_le_bytes(b) as usize)
+    }
+
+    fn float(&mut self) -> Result<Float, SimpleModelError> {
+        let mut b = [0; FLOAT_SIZE];
+        b.copy_from_slice(self.take(FLOAT_SIZE)?);
+        Ok(Float::from_le_bytes(b))
+    }
+}
+
+impl SimulationStateHeader {
+    /// Writes the layout of this header (i.e., its elements and
+    /// category counts) in a compact binary form
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut ret = HEADER_MAGIC.to_vec();
+        ret.extend((self.n_individual() as u64).to_le_bytes());
+        ret.extend((self.n_operational() as u64).to_le_bytes());
+        ret.extend((self.len() as u64).to_le_bytes());
+        for e in self.elements.iter() {
+            let fields = e.variant_fields();
+            let name = e.variant_name().as_bytes();
+            ret.push(name.len() as u8);
+            ret.extend(name);
+            ret.push(fields.len() as u8);
+            for f in fields {
+                ret.extend((f as u64).to_le_bytes());
+            }
+        }
+        ret
+    }
+
+    /// Reads a header written by [`SimulationStateHeader::to_bytes`].
+    ///
+    /// The returned header has no `default_values`, and its elements are
+    /// not mapped into any [`SimpleModel`](crate::SimpleModel). It is meant
+    /// to be compared with the header of the current model (see
+    /// [`SimulationStateHeader::check_layout`]).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SimpleModelError> {
+        let mut reader = Reader { bytes, position: 0 };
+        let header = Self::read(&mut reader)?;
+        if reader.position != bytes.len() {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!("unexpected data after byte {}", reader.position),
+            });
+        }
+        Ok(header)
+    }
+
+    /// Reads a header from a [`Reader`]
+    fn read(reader: &mut Reader) -> Result<Self, SimpleModelError> {
+        reader.magic(HEADER_MAGIC)?;
+        let n_individual = reader.usize()?;
+        let n_operational = reader.usize()?;
+        let n = reader.usize()?;
+
+        let mut header = SimulationStateHeader::new();
+        for _ in 0..n {
+            let variant = reader.name()?;
+            let n_fields = reader.u8()? as usize;
+            let fields = (0..n_fields)
+                .map(|_| reader.usize())
+                .collect::<Result<Vec<usize>, _>>()?;
+            let e = SimulationStateElement::from_variant(variant, &fields).ok_or_else(|| {
+                SimpleModelError::InvalidSnapshot {
+                    message: format!(
+                        "unknown element (variant '{}' with {} fields)",
+                        variant, n_fields
+                    ),
+                }
+            })?;
+            header.push(e, 0.);
+        }
+        if !header.is_sorted() {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: "the elements are not sorted by category".to_string(),
+            });
+        }
+        header.take_values();
+
+        if header.n_individual() != n_individual || header.n_operational() != n_operational {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!(
+                    "the header declares {} personal and {} operational elements, but contains {} and {}",
+                    n_individual,
+                    n_operational,
+                    header.n_individual(),
+                    header.n_operational()
+                ),
+            });
+        }
+        Ok(header)
+    }
+
+    /// Checks that a `stored` header (e.g., read from a snapshot) has
+    /// the same layout as this one, so a [`SimulationState`] written
+    /// with one can be used with the other.
+    pub fn check_layout(&self, stored: &SimulationStateHeader) -> Result<(), SimpleModelError> {
+        if self.len() != stored.len() {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!(
+                    "the stored state has {} elements, but the current one has {}",
+                    stored.len(),
+                    self.len()
+                ),
+            });
+        }
+        if let Some(i) = (0..self.len()).find(|i| self[*i] != stored[*i]) {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!(
+                    "element {} of the stored state is {:?}, but it is {:?} in the current one",
+                    i, stored[i], self[i]
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Writes a snapshot of `state` (i.e., this header followed by the
+    /// values in `state`) in a compact binary form
+    ///
+    /// # Panics
+    ///
+    /// Panics if `state` does not have one value per element
+    pub fn snapshot_to_bytes(&self, state: &SimulationState) -> Vec<u8> {
+        assert_eq!(
+            state.len(),
+            self.len(),
+            "The SimulationState does not match the SimulationStateHeader"
+        );
+        let mut ret = self.to_bytes();
+        ret.extend(STATE_MAGIC);
+        ret.push(FLOAT_SIZE as u8);
+        ret.extend((state.len() as u64).to_le_bytes());
+        for v in state.iter() {
+            ret.extend(v.to_le_bytes());
+        }
+        ret
+    }
+
+    /// Reads a snapshot written by [`SimulationStateHeader::snapshot_to_bytes`],
+    /// returning its [`SimulationState`].
+    ///
+    /// The header stored in the snapshot must have the same layout as this
+    /// one (which is normally rebuilt from the current model by calling
+    /// [`SimpleModel::build_state_header`](crate::SimpleModel::build_state_header)).
+    /// Otherwise, the values would be assigned to the wrong elements, so an
+    /// error is returned.
+    pub fn snapshot_from_bytes(&self, bytes: &[u8]) -> Result<SimulationState, SimpleModelError> {
+        let mut reader = Reader { bytes, position: 0 };
+        let stored = Self::read(&mut reader)?;
+        self.check_layout(&stored)?;
+
+        reader.magic(STATE_MAGIC)?;
+        let float_size = reader.u8()? as usize;
+        if float_size != FLOAT_SIZE {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!(
+                    "the snapshot stores {}-byte values, but this build uses {}-byte values",
+                    float_size, FLOAT_SIZE
+                ),
+            });
+        }
+        let n = reader.usize()?;
+        if n != self.len() {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!(
+                    "the snapshot has {} values, but its header has {} elements",
+                    n,
+                    self.len()
+                ),
+            });
+        }
+        let state = (0..n)
+            .map(|_| reader.float())
+            .collect::<Result<SimulationState, _>>()?;
+        if reader.position != bytes.len() {
+            return Err(SimpleModelError::InvalidSnapshot {
+                message: format!("unexpected data after byte {}", reader.position),
+            });
+        }
+        Ok(state)
+    }
+
+    /// Writes a snapshot of `state` into a file (see
+    /// [`SimulationStateHeader::snapshot_to_bytes`])
+    pub fn write_snapshot(
+        &self,
+        state: &SimulationState,
+        filename: String,
+    ) -> Result<(), SimpleModelError> {
+        match std::fs::write(filename.clone(), self.snapshot_to_bytes(state)) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(SimpleModelError::IO {
+                filename,
+                message: format!("Could not write snapshot: {}", e),
+            }),
+        }
+    }
+
+    /// Reads a snapshot from a file (see
+    /// [`SimulationStateHeader::snapshot_from_bytes`])
+    pub fn read_snapshot(&self, filename: String) -> Result<SimulationState, SimpleModelError> {
+        match std::fs::read(filename.clone()) {
+            Ok(bytes) => self.snapshot_from_bytes(&bytes),
+            Err(e) => Err(SimpleModelError::IO {
+                filename,
+                message: format!("Could not read snapshot: {}", e),
+            }),
+        }
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+
+    #[test]
+    fn test_snapshot() {
+        let src = b"
+Space { name: \"Bedroom\" }
+Space { name: \"Kitchen\" }
+Luminaire { name: \"the lamp\", target_space: \"Bedroom\" }
+AirExchange { name: \"the exchange\", from_space: \"Kitchen\", to_space: \"Bedroom\" }
+";
+        let (model, _) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let mut header = model.build_state_header();
+        let mut state = header.take_values().unwrap();
+        let i = model.spaces[1].dry_bulb_temperature_index().unwrap();
+        state[i] = 17.5;
+
+        // The header goes back and forth
+        let stored = SimulationStateHeader::from_bytes(&header.to_bytes()).unwrap();
+        assert_eq!(stored.elements, header.elements);
+        assert_eq!(stored.n_individual(), header.n_individual());
+        assert_eq!(stored.n_operational(), header.n_operational());
+        assert!(header.check_layout(&stored).is_ok());
+
+        // And so does the state, with a header rebuilt from the model
+        let bytes = header.snapshot_to_bytes(&state);
+        let rebuilt = model.build_state_header();
+        assert_eq!(rebuilt.snapshot_from_bytes(&bytes).unwrap(), state);
+
+        // Truncated data is rejected
+        assert!(rebuilt
+            .snapshot_from_bytes(&bytes[..bytes.len() - 1])
+            .is_err());
+
+        // Elements are stored by name, so unknown ones are reported
+        let mut bytes = HEADER_MAGIC.to_vec();
+        for n in [0_u64, 0, 1] {
+            bytes.extend(n.to_le_bytes());
+        }
+        bytes.push(12);
+        bytes.extend(b"NotAnElement");
+        bytes.push(1);
+        bytes.extend(0_u64.to_le_bytes());
+        match SimulationStateHeader::from_bytes(&bytes) {
+            Err(SimpleModelError::InvalidSnapshot { message }) => {
+                assert!(message.contains("'NotAnElement'"), "{}", message)
+            }
+            _ => panic!("Expecting an InvalidSnapshot error"),
+        }
+        bytes.truncate(4 + 3 * 8 + 1);
+        bytes.extend(b"SpaceDryBulbTemperature");
+        bytes[4 + 3 * 8] = 23;
+        bytes.push(1);
+        bytes.extend(1_u64.to_le_bytes());
+        let stored = SimulationStateHeader::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            stored.elements,
+            vec![SimulationStateElement::SpaceDryBulbTemperature(1)]
+        );
+
+        // A different model is rejected
+        let src = b"
+Space { name: \"Bedroom\" }
+Space { name: \"Kitchen\" }
+Luminaire { name: \"the lamp\", target_space: \"Bedroom\" }
+";
+        let (other, _) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let other = other.build_state_header();
+        match other.snapshot_from_bytes(&bytes) {
+            Err(SimpleModelError::InvalidSnapshot { message }) => {
+                assert!(message.contains("elements"), "{}", message)
+            }
+            _ => panic!("Expecting an InvalidSnapshot error"),
+        }
+    }
+}
@@ -20,16 +20,42 @@ SOFTWARE.
 
 use crate::Float;
 use derive::{ObjectAPI, ObjectIO};
+use geometry3d::{Point3D, Polygon3D, Vector3D};
+use std::cell::Cell;
 use std::rc::Rc;
 
+use crate::boundary::Boundary;
 use crate::building::Building;
+use crate::fenestration::Fenestration;
+use crate::hvac::HVAC;
 use crate::infiltration::Infiltration;
+use crate::luminaire::Luminaire;
 use crate::model::SimpleModel;
-use crate::simulation_state_element::StateElementField;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::surface::Surface;
+
+/// The distance (in meters) under which two vertices are
+/// considered to be the same
+const DISTANCE_TOLERANCE: Float = 1e-3;
+
+/// Surfaces whose outward normal points down more than this
+/// (i.e., tilted less than 30 degrees from horizontal) are floors
+const FLOOR_NORMAL_Z: Float = -0.866;
+
+/// Surfaces whose outward normal has a vertical component smaller
+/// than this (i.e., tilted less than 30 degrees from vertical) are walls
+const WALL_NORMAL_Z: Float = 0.5;
+
+/// The temperature (in C) at which the air of—or entering into—a
+/// [`Space`] starts when building a [`SimulationStateHeader`]
+pub(crate) const INITIAL_AIR_TEMPERATURE: Float = 22.;
 
 /// Represents a space within a building. This will
 /// often be a room, but it might also be half a room
 #[derive(ObjectIO, ObjectAPI, Clone)]
+#[api_methods(floor_area(model), envelope_area(model))]
 pub struct Space {
     /// The name of the space
     pub name: String,
@@ -38,13 +64,18 @@ pub struct Space {
     index: Option<usize>,
 
     /// Volume of the space
-    pub volume: Option<Float>,
+    pub volume: Cell<Option<Float>>,
 
     /// The infiltration in the space
     infiltration: Option<Infiltration>,
 
+    /// A multiplier (usually from 0 to 1) that modulates
+    /// the `infiltration` over time
+    infiltration_schedule: Option<Rc<Schedule>>,
+
     /// The importance of this space over time
-    // importance : Option<Box<dyn Schedule<Float>>>,
+    importance: Option<Rc<Schedule>>,
+
     building: Option<Rc<Building>>,
 
     #[physical]
@@ -69,6 +100,289 @@ pub struct Space {
     ventilation_temperature: StateElementField,
 }
 
+impl Space {
+    /// Checks whether `space` is this very [`Space`] (i.e., the same
+    /// object, not just one with the same name)
+    fn is(&self, space: &Rc<Space>) -> bool {
+        std::ptr::eq(Rc::as_ptr(space), self)
+    }
+
+    /// Checks which side of a surface or fenestration—with the
+    /// given boundaries—faces this [`Space`].
+    ///
+    /// Returns the orientation of the outward normal (`1.0` if it is the
+    /// normal of the polygon, `-1.0` if it is the opposite) and the
+    /// boundary on the other side, or `None` if they do not bound this space.
+    fn side<'a>(
+        &self,
+        front: Option<&'a Boundary>,
+        back: Option<&'a Boundary>,
+    ) -> Option<(Float, Option<&'a Boundary>)> {
+        let is_self = |b: Option<&Boundary>| matches!(b, Some(Boundary::Space(s)) if self.is(s));
+        if is_self(back) {
+            Some((1.0, front))
+        } else if is_self(front) {
+            Some((-1.0, back))
+        } else {
+            None
+        }
+    }
+
+    /// The [`Surface`]s of the model that bound this [`Space`] (i.e.,
+    /// that have it as their front or back [`Boundary`]), each with the
+    /// orientation of its outward normal and the boundary on its other side
+    fn bounding_surfaces_with_side<'a>(
+        &self,
+        model: &'a SimpleModel,
+    ) -> Vec<(&'a Rc<Surface>, Float, Option<&'a Boundary>)> {
+        model
+            .surfaces
+            .iter()
+            .filter_map(|s| {
+                self.side(s.front_boundary().ok(), s.back_boundary().ok())
+                    .map(|(sign, other)| (s, sign, other))
+            })
+            .collect()
+    }
+
+    /// The [`Fenestration`]s of the model that bound this [`Space`], each
+    /// with the orientation of its outward normal and the boundary on its
+    /// other side
+    fn bounding_fenestrations_with_side<'a>(
+        &self,
+        model: &'a SimpleModel,
+    ) -> Vec<(&'a Rc<Fenestration>, Float, Option<&'a Boundary>)> {
+        model
+            .fenestrations
+            .iter()
+            .filter_map(|s| {
+                self.side(s.front_boundary().ok(), s.back_boundary().ok())
+                    .map(|(sign, other)| (s, sign, other))
+            })
+            .collect()
+    }
+
+    /// Returns the [`Surface`]s of the model that have this [`Space`]
+    /// as their front or back [`Boundary`]
+    pub fn bounding_surfaces(&self, model: &SimpleModel) -> Vec<Rc<Surface>> {
+        self.bounding_surfaces_with_side(model)
+            .into_iter()
+            .map(|(s, ..)| Rc::clone(s))
+            .collect()
+    }
+
+    /// Returns the [`Fenestration`]s of the model that have this [`Space`]
+    /// as their front or back [`Boundary`]
+    pub fn bounding_fenestrations(&self, model: &SimpleModel) -> Vec<Rc<Fenestration>> {
+        self.bounding_fenestrations_with_side(model)
+            .into_iter()
+            .map(|(s, ..)| Rc::clone(s))
+            .collect()
+    }
+
+    /// Returns the [`HVAC`]s of the model that serve this [`Space`]
+    pub fn hvacs(&self, model: &SimpleModel) -> Vec<HVAC> {
+        model
+            .hvacs
+            .iter()
+            .filter(|h| h.target_spaces().iter().any(|s| self.is(s)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the [`Luminaire`]s of the model that are in this [`Space`]
+    pub fn luminaires(&self, model: &SimpleModel) -> Vec<Rc<Luminaire>> {
+        model
+            .luminaires
+            .iter()
+            .filter(|l| matches!(l.target_space(), Ok(s) if self.is(s)))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the floor area of the [`Space`]: the area of the bounding
+    /// [`Surface`]s whose normal, pointing away from the space, points down
+    /// (i.e., less than 30 degrees from vertical)
+    pub fn floor_area(&self, model: &SimpleModel) -> Float {
+        self.bounding_surfaces_with_side(model)
+            .iter()
+            .filter(|(s, sign, _)| sign * s.vertices.normal().z < FLOOR_NORMAL_Z)
+            .map(|(s, ..)| s.area())
+            .sum()
+    }
+
+    /// Returns the area of the envelope of the [`Space`]: the area of the
+    /// bounding [`Surface`]s and [`Fenestration`]s that lead to the exterior
+    /// or to the ground (i.e., not to another `Space`)
+    pub fn envelope_area(&self, model: &SimpleModel) -> Float {
+        let is_envelope = |other: &Option<&Boundary>| !matches!(other, Some(Boundary::Space(_)));
+        let surfaces: Float = self
+            .bounding_surfaces_with_side(model)
+            .iter()
+            .filter(|(_, _, other)| is_envelope(other))
+            .map(|(s, ..)| s.area())
+            .sum();
+        let fenestrations: Float = self
+            .bounding_fenestrations_with_side(model)
+            .iter()
+            .filter(|(_, _, other)| is_envelope(other))
+            .map(|(f, ..)| f.area())
+            .sum();
+        surfaces + fenestrations
+    }
+
+    /// Returns the window-to-wall ratio of the [`Space`]: the area of the
+    /// vertical [`Fenestration`]s leading to the exterior, divided by the
+    /// gross area of the walls leading to the exterior (i.e., the area of their
+    /// [`Surface`]s plus that of those fenestrations).
+    ///
+    /// Surfaces and fenestrations tilted less than 30 degrees from vertical
+    /// are considered vertical. Returns `None` if the space has no
+    /// exterior walls.
+    pub fn window_wall_ratio(&self, model: &SimpleModel) -> Option<Float> {
+        let is_exterior_wall = |polygon: &Polygon3D, other: &Option<&Boundary>| {
+            other.is_none() && polygon.normal().z.abs() < WALL_NORMAL_Z
+        };
+        let walls: Float = self
+            .bounding_surfaces_with_side(model)
+            .iter()
+            .filter(|(s, _, other)| is_exterior_wall(&s.vertices, other))
+            .map(|(s, ..)| s.area())
+            .sum();
+        let windows: Float = self
+            .bounding_fenestrations_with_side(model)
+            .iter()
+            .filter(|(f, _, other)| is_exterior_wall(&f.vertices, other))
+            .map(|(f, ..)| f.area())
+            .sum();
+        let gross = walls + windows;
+        if gross > 0.0 {
+            Some(windows / gross)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the volume enclosed by the bounding [`Surface`]s of the
+    /// [`Space`], or `None` if they do not form a closed shell.
+    ///
+    /// The shell is considered closed when every edge of the (outer loop of
+    /// the) surfaces is shared by exactly one other surface, so neighbouring
+    /// surfaces need to share their vertices. Fenestrations are assumed
+    /// to be drawn within their surfaces, so they are not considered.
+    pub fn enclosed_volume(&self, model: &SimpleModel) -> Option<Float> {
+        let surfaces = self.bounding_surfaces_with_side(model);
+        if surfaces.is_empty() {
+            return None;
+        }
+
+        // Vertices of each surface, sorted so that their normal points outwards
+        let loops: Vec<Vec<Point3D>> = surfaces
+            .iter()
+            .map(|(s, sign, _)| {
+                let mut vertices = s.vertices.outer().vertices().to_vec();
+                if *sign < 0.0 {
+                    vertices.reverse();
+                }
+                vertices
+            })
+            .collect();
+        let edges: Vec<(Point3D, Point3D)> = loops
+            .iter()
+            .flat_map(|vertices| {
+                let n = vertices.len();
+                (0..n).map(move |i| (vertices[i], vertices[(i + 1) % n]))
+            })
+            .collect();
+
+        // In a closed shell, every edge is traversed once in each direction
+        let same = |a: Point3D, b: Point3D| (a - b).length() <= DISTANCE_TOLERANCE;
+        let closed = edges.iter().all(|(a, b)| {
+            edges
+                .iter()
+                .filter(|(c, d)| same(*a, *d) && same(*b, *c))
+                .count()
+                == 1
+        });
+        if !closed {
+            return None;
+        }
+
+        // Divergence theorem: V = 1/3 * sum( (p * n) * A )
+        let origin = Point3D::new(0., 0., 0.);
+        let volume: Float = surfaces
+            .iter()
+            .zip(loops.iter())
+            .map(|((s, sign, _), vertices)| {
+                let normal: Vector3D = s.vertices.normal() * *sign;
+                let p: Vector3D = vertices[0] - origin;
+                (p * normal) * s.area()
+            })
+            .sum::<Float>()
+            / 3.;
+
+        if volume > 0.0 {
+            Some(volume)
+        } else {
+            None
+        }
+    }
+
+    /// Pushes the physical states of the [`Space`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// Its `brightness` and `loudness` are not included, as there are
+    /// no state elements for them yet
+    pub(crate) fn push_physical_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self.index().expect("Space has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::SpaceDryBulbTemperature(obj_index),
+            INITIAL_AIR_TEMPERATURE,
+        );
+        self.set_dry_bulb_temperature_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SpaceInfiltrationVolume(obj_index),
+            0.,
+        );
+        self.set_infiltration_volume_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SpaceInfiltrationTemperature(obj_index),
+            INITIAL_AIR_TEMPERATURE,
+        );
+        self.set_infiltration_temperature_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SpaceVentilationVolume(obj_index),
+            0.,
+        );
+        self.set_ventilation_volume_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SpaceVentilationTemperature(obj_index),
+            INITIAL_AIR_TEMPERATURE,
+        );
+        self.set_ventilation_temperature_index(state_index);
+    }
+}
+
+impl SimpleModel {
+    /// Sets the `volume` of the [`Space`]s that do not have one and are
+    /// enclosed by their [`Surface`]s (see [`Space::enclosed_volume`]).
+    ///
+    /// Returns the names of the spaces whose volume was set.
+    pub fn fill_missing_volumes(&self) -> Vec<String> {
+        let mut filled = Vec::new();
+        for space in self.spaces.iter() {
+            if space.volume.get().is_some() {
+                continue;
+            }
+            if let Some(volume) = space.enclosed_volume(self) {
+                space.set_volume(volume);
+                filled.push(space.name.clone());
+            }
+        }
+        filled
+    }
+}
+
 impl SimpleModel {
     /// Adds a [`Space`] to the [`SimpleModel`]
     pub fn add_space(&mut self, mut add: Space) -> Rc<Space> {
@@ -86,6 +400,7 @@ impl SimpleModel {
 #[cfg(test)]
 mod testing {
     use super::*;
+    use crate::scanner::SimpleScanner;
 
     #[cfg(feature = "float")]
     const EPSILON: f32 = std::f32::EPSILON;
@@ -97,13 +412,13 @@ mod testing {
     fn test_new() {
         let space_name = "the_space".to_string();
 
-        let mut space = Space::new(space_name.clone());
+        let space = Space::new(space_name.clone());
         assert_eq!(space.name, space_name);
         assert!(space.volume().is_err());
 
         let vol = 987.12312;
         space.set_volume(vol);
-        assert_eq!(*space.volume().unwrap(), vol);
+        assert_eq!(space.volume().unwrap(), vol);
 
         let i = 91;
         assert!(space.dry_bulb_temperature.borrow().is_none());
@@ -140,9 +455,140 @@ mod testing {
         let space = Space::from_bytes(1, bytes, &mut building).unwrap();
 
         assert_eq!(space.name, "A Space".to_string());
-        assert!((1.2 - space.volume.unwrap()).abs() < EPSILON);
+        assert!((1.2 - space.volume.get().unwrap()).abs() < EPSILON);
         if let Some(Infiltration::Constant(v)) = space.infiltration {
             assert!((2.2 - v).abs() < EPSILON);
         }
     }
+
+    #[test]
+    fn test_related_objects_by_identity() {
+        // Two spaces with the same name are still different spaces
+        let mut model = SimpleModel::new("the model".to_string());
+        let mut header = SimulationStateHeader::new();
+        let first = model.add_space(Space::new("the space".to_string()));
+        let second = model.add_space(Space::new("the space".to_string()));
+
+        let mut luminaire = Luminaire::new("the luminaire".to_string());
+        luminaire.set_target_space(Rc::clone(&second));
+        let luminaire = model.add_luminaire(luminaire, &mut header);
+
+        assert!(first.luminaires(&model).is_empty());
+        let found = second.luminaires(&model);
+        assert_eq!(found.len(), 1);
+        assert!(Rc::ptr_eq(&found[0], &luminaire));
+    }
+
+    #[test]
+    fn test_geometry() {
+        // A 2 x 1 x 1 box. The ceiling faces the inside of the
+        // box and the east wall leads to another space.
+        let src = b"
+Substance::Normal { name: \"the substance\" }
+
+Material {
+    name: \"the material\",
+    substance: \"the substance\",
+    thickness: 0.1
+}
+
+Construction {
+    name: \"the construction\",
+    materials: [\"the material\"]
+}
+
+Space { name: \"box\" }
+Space { name: \"other\" }
+
+Surface {
+    name: \"floor\",
+    construction: \"the construction\",
+    back_boundary: Boundary::Space(\"box\"),
+    front_boundary: Boundary::Ground(),
+    vertices: [0, 0, 0, 0, 1, 0, 2, 1, 0, 2, 0, 0]
+}
+
+Surface {
+    name: \"ceiling\",
+    construction: \"the construction\",
+    front_boundary: Boundary::Space(\"box\"),
+    vertices: [0, 0, 1, 0, 1, 1, 2, 1, 1, 2, 0, 1]
+}
+
+Surface {
+    name: \"south\",
+    construction: \"the construction\",
+    back_boundary: Boundary::Space(\"box\"),
+    vertices: [0, 0, 0, 2, 0, 0, 2, 0, 1, 0, 0, 1]
+}
+
+Surface {
+    name: \"north\",
+    construction: \"the construction\",
+    back_boundary: Boundary::Space(\"box\"),
+    vertices: [0, 1, 0, 0, 1, 1, 2, 1, 1, 2, 1, 0]
+}
+
+Surface {
+    name: \"west\",
+    construction: \"the construction\",
+    back_boundary: Boundary::Space(\"box\"),
+    vertices: [0, 0, 0, 0, 0, 1, 0, 1, 1, 0, 1, 0]
+}
+
+Surface {
+    name: \"east\",
+    construction: \"the construction\",
+    back_boundary: Boundary::Space(\"box\"),
+    front_boundary: Boundary::Space(\"other\"),
+    vertices: [2, 0, 0, 2, 1, 0, 2, 1, 1, 2, 0, 1]
+}
+
+Fenestration {
+    name: \"the window\",
+    construction: \"the construction\",
+    operation_type: FenestrationPositions::FixedClosed(),
+    fenestration_type: FenestrationType::Window(),
+    back_boundary: Boundary::Space(\"box\"),
+    vertices: [0.5, 0, 0.25, 1.5, 0, 0.25, 1.5, 0, 0.75, 0.5, 0, 0.75]
+}
+";
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        let the_box = Rc::clone(&model.spaces[0]);
+        let other = Rc::clone(&model.spaces[1]);
+
+        let names: Vec<String> = the_box
+            .bounding_surfaces(&model)
+            .iter()
+            .map(|s| s.name.clone())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["floor", "ceiling", "south", "north", "west", "east"]
+        );
+        assert_eq!(the_box.bounding_fenestrations(&model).len(), 1);
+        assert_eq!(other.bounding_surfaces(&model).len(), 1);
+
+        assert!((the_box.floor_area(&model) - 2.0).abs() < 1e-5);
+        assert!(other.floor_area(&model).abs() < 1e-5);
+        assert!((the_box.envelope_area(&model) - 9.5).abs() < 1e-5);
+        assert!((the_box.window_wall_ratio(&model).unwrap() - 0.5 / 5.5).abs() < 1e-5);
+        assert!(other.window_wall_ratio(&model).is_none());
+
+        assert!((the_box.enclosed_volume(&model).unwrap() - 2.0).abs() < 1e-5);
+        assert!(other.enclosed_volume(&model).is_none());
+
+        // Fill the volume, which is seen by everything referencing the Space
+        assert_eq!(model.fill_missing_volumes(), vec!["box".to_string()]);
+        assert!((model.spaces[0].volume().unwrap() - 2.0).abs() < 1e-5);
+        assert!((the_box.volume().unwrap() - 2.0).abs() < 1e-5);
+        assert!(model.spaces[1].volume().is_err());
+        if let Ok(Boundary::Space(s)) = model.fenestrations[0].back_boundary() {
+            assert!(Rc::ptr_eq(s, &model.spaces[0]));
+            assert!((s.volume().unwrap() - 2.0).abs() < 1e-5);
+        } else {
+            panic!("Expecting the window to lead to the box");
+        }
+        assert!(model.fill_missing_volumes().is_empty());
+    }
 }
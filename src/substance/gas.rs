@@ -18,7 +18,28 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 */
 
+use crate::substance::Substance;
+use crate::{Float, SimpleModelError};
 use derive::ObjectIO;
+use std::rc::Rc;
+
+/// The universal gas constant, in J/kmol.K
+const UNIVERSAL_GAS_CONSTANT: Float = 8314.462;
+
+/// The atmospheric pressure, in Pa
+const ATMOSPHERIC_PRESSURE: Float = 101325.;
+
+/// The coefficients `[a, b, c]` of a property that
+/// depends on the temperature $`T`$ (in K) as $`a + bT + cT^2`$
+type Coefficients = [Float; 3];
+
+/// The components of a mixture of gases, and their (normalized) fractions
+type Mixture = Vec<(Rc<Gas>, Float)>;
+
+/// Evaluates the polynomial represented by some [`Coefficients`]
+fn evaluate(c: &Coefficients, temperature: Float) -> Float {
+    c[0] + c[1] * temperature + c[2] * temperature * temperature
+}
 
 /// Represent a common gas, with known physical properties
 #[derive(Clone, ObjectIO)]
@@ -36,12 +57,99 @@ pub enum StandardGas {
     Xenon,
 }
 
+/// The properties of the [`StandardGas`], from ISO 15099 (Table B.1)
+impl StandardGas {
+    /// The coefficients of the thermal conductivity, in W/m.K
+    fn thermal_conductivity_coefficients(&self) -> Coefficients {
+        match self {
+            Self::Air => [2.873e-3, 7.760e-5, 0.],
+            Self::Argon => [2.285e-3, 5.149e-5, 0.],
+            Self::Krypton => [9.443e-4, 2.826e-5, 0.],
+            Self::Xenon => [4.538e-4, 1.723e-5, 0.],
+        }
+    }
+
+    /// The coefficients of the dynamic viscosity, in Pa.s
+    fn dynamic_viscosity_coefficients(&self) -> Coefficients {
+        match self {
+            Self::Air => [3.723e-6, 4.940e-8, 0.],
+            Self::Argon => [3.379e-6, 6.451e-8, 0.],
+            Self::Krypton => [2.213e-6, 7.777e-8, 0.],
+            Self::Xenon => [1.069e-6, 7.414e-8, 0.],
+        }
+    }
+
+    /// The coefficients of the specific heat capacity at
+    /// constant pressure, in J/kg.K
+    fn specific_heat_capacity_coefficients(&self) -> Coefficients {
+        match self {
+            Self::Air => [1002.737, 1.2324e-2, 0.],
+            Self::Argon => [521.9285, 0., 0.],
+            Self::Krypton => [248.0907, 0., 0.],
+            Self::Xenon => [158.3397, 0., 0.],
+        }
+    }
+
+    /// The molecular mass of the gas, in kg/kmol
+    pub fn molecular_mass(&self) -> Float {
+        match self {
+            Self::Air => 28.97,
+            Self::Argon => 39.948,
+            Self::Krypton => 83.80,
+            Self::Xenon => 131.30,
+        }
+    }
+
+    /// The thermal conductivity of the gas (in W/m.K) at a
+    /// certain `temperature` (in K)
+    pub fn thermal_conductivity(&self, temperature: Float) -> Float {
+        evaluate(&self.thermal_conductivity_coefficients(), temperature)
+    }
+
+    /// The dynamic viscosity of the gas (in Pa.s) at a
+    /// certain `temperature` (in K)
+    pub fn dynamic_viscosity(&self, temperature: Float) -> Float {
+        evaluate(&self.dynamic_viscosity_coefficients(), temperature)
+    }
+
+    /// The specific heat capacity at constant pressure of the
+    /// gas (in J/kg.K) at a certain `temperature` (in K)
+    pub fn specific_heat_capacity(&self, temperature: Float) -> Float {
+        evaluate(&self.specific_heat_capacity_coefficients(), temperature)
+    }
+}
+
 /// Represents a Normal; that is to say, a physical
 /// materiality with physical properties. The name Normal
 /// has been chosen instead of Material to respect EnergyPlus'
 /// and other software's terminology (which does not include
 /// Substace, but it does include Material, which is essentially
 /// a Normal with a thickness).
+///
+/// A `Gas` can be either a [`StandardGas`] (given by its `kind`); a
+/// custom gas (given by the coefficients of its properties and its
+/// `molecular_mass`); or a mixture of other gases (given by its
+/// `mixture_components` and their `mixture_fractions`).
+///
+/// ### Example
+///
+/// ```rs
+/// Substance::Gas {
+///     name: "argon",
+///     kind: StandardGas::Argon()
+/// }
+///
+/// Substance::Gas {
+///     name: "air",
+///     kind: StandardGas::Air()
+/// }
+///
+/// Substance::Gas {
+///     name: "90% argon",
+///     mixture_components: ["argon", "air"],
+///     mixture_fractions: [0.9, 0.1]
+/// }
+/// ```
 #[derive(Clone, ObjectIO)]
 pub struct Gas {
     /// The name of the Normal. Should be unique for each
@@ -53,12 +161,271 @@ pub struct Gas {
 
     /// A predefined gas
     kind: Option<StandardGas>,
+
+    /// The coefficients `[a, b, c]` of the thermal conductivity
+    /// (in W/m.K) of a custom gas, which is calculated
+    /// as $`a + bT + cT^2`$ (with $`T`$ in K)
+    thermal_conductivity_coefficients: Option<Vec<Float>>,
+
+    /// The coefficients `[a, b, c]` of the dynamic viscosity
+    /// (in Pa.s) of a custom gas, which is calculated
+    /// as $`a + bT + cT^2`$ (with $`T`$ in K)
+    dynamic_viscosity_coefficients: Option<Vec<Float>>,
+
+    /// The coefficients `[a, b, c]` of the specific heat capacity
+    /// at constant pressure (in J/kg.K) of a custom gas, which is
+    /// calculated as $`a + bT + cT^2`$ (with $`T`$ in K)
+    specific_heat_capacity_coefficients: Option<Vec<Float>>,
+
+    /// The molecular mass of a custom gas, in kg/kmol
+    molecular_mass: Option<Float>,
+
+    /// The gases that form a mixture
+    mixture_components: Option<Vec<Substance>>,
+
+    /// The molar (i.e., volumetric) fraction of each of the
+    /// `mixture_components`. They are normalized, so they
+    /// do not need to add up to 1.
+    mixture_fractions: Option<Vec<Float>>,
 }
 
 impl Gas {
     pub fn wrap(self) -> crate::substance::Substance {
         crate::substance::Substance::Gas(std::rc::Rc::new(self))
     }
+
+    /// Builds an error reporting that this gas is invalid
+    fn invalid(&self, message: String) -> SimpleModelError {
+        SimpleModelError::InvalidProperty {
+            object_type: "Gas".to_string(),
+            object_name: self.name.clone(),
+            message,
+        }
+    }
+
+    /// Checks that a field holds valid [`Coefficients`]
+    fn coefficients(
+        &self,
+        field: &str,
+        values: Result<&Vec<Float>, SimpleModelError>,
+    ) -> Result<Coefficients, SimpleModelError> {
+        match values?.as_slice() {
+            [a, b, c] => Ok([*a, *b, *c]),
+            v => Err(self.invalid(format!(
+                "'{}' must have 3 coefficients, but it has {}",
+                field,
+                v.len()
+            ))),
+        }
+    }
+
+    /// Returns the components of a mixture and their normalized
+    /// fractions, or `None` if this gas is not a mixture
+    fn mixture(&self) -> Result<Option<Mixture>, SimpleModelError> {
+        let components = match self.mixture_components() {
+            Ok(c) => c,
+            Err(_) => return Ok(None),
+        };
+        let fractions = self.mixture_fractions()?;
+        if components.len() != fractions.len() {
+            return Err(self.invalid(format!(
+                "it has {} mixture components but {} mixture fractions",
+                components.len(),
+                fractions.len()
+            )));
+        }
+        let total: Float = fractions.iter().sum();
+        if total <= 0.0 || fractions.iter().any(|x| *x < 0.0) {
+            return Err(self.invalid("mixture fractions must be positive".to_string()));
+        }
+        let mut ret = Vec::with_capacity(components.len());
+        for (component, fraction) in components.iter().zip(fractions.iter()) {
+            match component {
+                Substance::Gas(g) => ret.push((Rc::clone(g), fraction / total)),
+                Substance::Normal(s) => {
+                    return Err(self.invalid(format!("mixture component '{}' is not a Gas", s.name)))
+                }
+            }
+        }
+        Ok(Some(ret))
+    }
+
+    /// Mixes a `property` of the components of a `mixture` as in ISO 15099
+    /// (Section 5.1.4), where `phi(i, j)` is the interaction coefficient
+    /// between components `i` and `j`.
+    fn mix_property<F: Fn(usize, usize) -> Float>(
+        mixture: &[(Rc<Gas>, Float)],
+        property: &[Float],
+        phi: F,
+    ) -> Float {
+        let mut ret = 0.0;
+        for (i, (_, x_i)) in mixture.iter().enumerate() {
+            let mut denominator = 0.0;
+            for (j, (_, x_j)) in mixture.iter().enumerate() {
+                denominator += x_j * phi(i, j);
+            }
+            ret += x_i * property[i] / denominator;
+        }
+        ret
+    }
+
+    /// The molecular masses of the components of a `mixture`
+    fn component_masses(mixture: &[(Rc<Gas>, Float)]) -> Result<Vec<Float>, SimpleModelError> {
+        mixture
+            .iter()
+            .map(|(gas, _)| gas.mean_molecular_mass())
+            .collect()
+    }
+
+    /// Mixes the dynamic `viscosity` of the components of a `mixture`, as
+    /// in ISO 15099 (Section 5.1.4.2)
+    fn mix_dynamic_viscosity(
+        mixture: &[(Rc<Gas>, Float)],
+        viscosity: &[Float],
+    ) -> Result<Float, SimpleModelError> {
+        let mass = Self::component_masses(mixture)?;
+        Ok(Self::mix_property(mixture, viscosity, |i, j| {
+            (1. + (viscosity[i] / viscosity[j]).sqrt() * (mass[j] / mass[i]).powf(0.25)).powi(2)
+                / (8. * (1. + mass[i] / mass[j])).sqrt()
+        }))
+    }
+
+    /// Mixes the thermal `conductivity` of the components of a `mixture`,
+    /// as in ISO 15099 (Section 5.1.4.3). The conductivity of each component
+    /// is split into a monatomic part (i.e., the one derived from its
+    /// viscosity) and a polyatomic part (i.e., the rest), which are mixed
+    /// with different interaction coefficients.
+    fn mix_thermal_conductivity(
+        mixture: &[(Rc<Gas>, Float)],
+        conductivity: &[Float],
+        temperature: Float,
+    ) -> Result<Float, SimpleModelError> {
+        let mass = Self::component_masses(mixture)?;
+        let mut monatomic = Vec::with_capacity(mixture.len());
+        for ((gas, _), m) in mixture.iter().zip(mass.iter()) {
+            monatomic
+                .push(15. / 4. * UNIVERSAL_GAS_CONSTANT / m * gas.dynamic_viscosity(temperature)?);
+        }
+        let polyatomic: Vec<Float> = conductivity
+            .iter()
+            .zip(monatomic.iter())
+            .map(|(k, k_mono)| k - k_mono)
+            .collect();
+
+        let phi = |i: usize, j: usize| {
+            (1. + (monatomic[i] / monatomic[j]).sqrt() * (mass[i] / mass[j]).powf(0.25)).powi(2)
+                / (8. * (1. + mass[i] / mass[j])).sqrt()
+        };
+        let psi = |i: usize, j: usize| {
+            phi(i, j)
+                * (1.
+                    + 2.41 * (mass[i] - mass[j]) * (mass[i] - 0.142 * mass[j])
+                        / (mass[i] + mass[j]).powi(2))
+        };
+        Ok(Self::mix_property(mixture, &monatomic, psi)
+            + Self::mix_property(mixture, &polyatomic, phi))
+    }
+
+    /// The molecular mass of the gas, in kg/kmol. For mixtures,
+    /// this is the mean molecular mass of its components.
+    pub fn mean_molecular_mass(&self) -> Result<Float, SimpleModelError> {
+        if let Ok(kind) = self.kind() {
+            return Ok(kind.molecular_mass());
+        }
+        match self.mixture()? {
+            Some(mixture) => {
+                let mut ret = 0.0;
+                for (gas, x) in mixture.iter() {
+                    ret += x * gas.mean_molecular_mass()?;
+                }
+                Ok(ret)
+            }
+            None => Ok(*self.molecular_mass()?),
+        }
+    }
+
+    /// The thermal conductivity of the gas (in W/m.K) at a
+    /// certain `temperature` (in K)
+    pub fn thermal_conductivity(&self, temperature: Float) -> Result<Float, SimpleModelError> {
+        if let Ok(kind) = self.kind() {
+            return Ok(kind.thermal_conductivity(temperature));
+        }
+        match self.mixture()? {
+            Some(mixture) => {
+                let mut property = Vec::with_capacity(mixture.len());
+                for (gas, _) in mixture.iter() {
+                    property.push(gas.thermal_conductivity(temperature)?);
+                }
+                Self::mix_thermal_conductivity(&mixture, &property, temperature)
+            }
+            None => {
+                let c = self.coefficients(
+                    "thermal_conductivity_coefficients",
+                    self.thermal_conductivity_coefficients(),
+                )?;
+                Ok(evaluate(&c, temperature))
+            }
+        }
+    }
+
+    /// The dynamic viscosity of the gas (in Pa.s) at a
+    /// certain `temperature` (in K)
+    pub fn dynamic_viscosity(&self, temperature: Float) -> Result<Float, SimpleModelError> {
+        if let Ok(kind) = self.kind() {
+            return Ok(kind.dynamic_viscosity(temperature));
+        }
+        match self.mixture()? {
+            Some(mixture) => {
+                let mut property = Vec::with_capacity(mixture.len());
+                for (gas, _) in mixture.iter() {
+                    property.push(gas.dynamic_viscosity(temperature)?);
+                }
+                Self::mix_dynamic_viscosity(&mixture, &property)
+            }
+            None => {
+                let c = self.coefficients(
+                    "dynamic_viscosity_coefficients",
+                    self.dynamic_viscosity_coefficients(),
+                )?;
+                Ok(evaluate(&c, temperature))
+            }
+        }
+    }
+
+    /// The specific heat capacity at constant pressure of the gas
+    /// (in J/kg.K) at a certain `temperature` (in K)
+    pub fn specific_heat_capacity(&self, temperature: Float) -> Result<Float, SimpleModelError> {
+        if let Ok(kind) = self.kind() {
+            return Ok(kind.specific_heat_capacity(temperature));
+        }
+        match self.mixture()? {
+            Some(mixture) => {
+                // Weighted by the mass of each component
+                let mut heat_capacity = 0.0;
+                let mut mass = 0.0;
+                for (gas, x) in mixture.iter() {
+                    let m = x * gas.mean_molecular_mass()?;
+                    heat_capacity += m * gas.specific_heat_capacity(temperature)?;
+                    mass += m;
+                }
+                Ok(heat_capacity / mass)
+            }
+            None => {
+                let c = self.coefficients(
+                    "specific_heat_capacity_coefficients",
+                    self.specific_heat_capacity_coefficients(),
+                )?;
+                Ok(evaluate(&c, temperature))
+            }
+        }
+    }
+
+    /// The density of the gas (in kg/m3) at a certain `temperature`
+    /// (in K) and at atmospheric pressure, assuming it is an ideal gas
+    pub fn density(&self, temperature: Float) -> Result<Float, SimpleModelError> {
+        Ok(ATMOSPHERIC_PRESSURE * self.mean_molecular_mass()?
+            / (UNIVERSAL_GAS_CONSTANT * temperature))
+    }
 }
 
 /***********/
@@ -86,4 +453,114 @@ mod testing {
         // assert!((2.2 - sub.specific_heat_capacity.unwrap()).abs() < EPSILON);
         // assert!((3.2 - sub.density.unwrap()).abs() < EPSILON);
     }
+
+    #[test]
+    fn test_standard_gas_properties() {
+        // ISO 15099, Table B.1
+        let k = StandardGas::Air.thermal_conductivity(283.15);
+        assert!((k - 0.02484).abs() < 1e-4, "found {}", k);
+        let k = StandardGas::Argon.thermal_conductivity(283.15);
+        assert!((k - 0.01687).abs() < 1e-4, "found {}", k);
+        let mu = StandardGas::Air.dynamic_viscosity(283.15);
+        assert!((mu - 1.771e-5).abs() < 1e-7, "found {}", mu);
+        let cp = StandardGas::Argon.specific_heat_capacity(283.15);
+        assert!((cp - 521.9285).abs() < 1e-4, "found {}", cp);
+        assert!((StandardGas::Xenon.molecular_mass() - 131.3).abs() < 1e-6);
+
+        let gas = Gas::new("some gas".to_string());
+        assert!(matches!(
+            gas.thermal_conductivity(283.15),
+            Err(SimpleModelError::UnassignedField { .. })
+        ));
+    }
+
+    #[test]
+    fn test_custom_gases_and_mixtures() {
+        let src = b"
+Substance::Gas {
+    name: \"argon\",
+    kind: StandardGas::Argon()
+}
+
+Substance::Gas {
+    name: \"custom\",
+    thermal_conductivity_coefficients: [0.002285, 0.00005149, 0],
+    dynamic_viscosity_coefficients: [0.000003379, 0.00000006451, 0],
+    specific_heat_capacity_coefficients: [521.9285, 0, 0],
+    molecular_mass: 39.948
+}
+
+Substance::Gas {
+    name: \"argon and air\",
+    mixture_components: [\"argon\", \"air\"],
+    mixture_fractions: [9, 1]
+}
+
+Substance::Gas {
+    name: \"air\",
+    kind: StandardGas::Air()
+}
+
+Substance::Gas {
+    name: \"bad custom\",
+    thermal_conductivity_coefficients: [0.002285, 0.00005149]
+}
+";
+        let (model, _header) = crate::scanner::SimpleScanner::new(src, 1)
+            .parse_model()
+            .unwrap();
+        let gas = |name: &str| -> Rc<Gas> {
+            for s in model.substances.iter() {
+                if let Substance::Gas(g) = s {
+                    if g.name == name {
+                        return Rc::clone(g);
+                    }
+                }
+            }
+            panic!("Gas '{}' not found", name)
+        };
+        let t = 283.15;
+
+        // A custom gas with the properties of Argon
+        let (argon, custom) = (gas("argon"), gas("custom"));
+        assert!(
+            (argon.thermal_conductivity(t).unwrap() - custom.thermal_conductivity(t).unwrap())
+                .abs()
+                < 1e-9
+        );
+        assert!(
+            (argon.dynamic_viscosity(t).unwrap() - custom.dynamic_viscosity(t).unwrap()).abs()
+                < 1e-12
+        );
+        assert!(
+            (argon.specific_heat_capacity(t).unwrap() - custom.specific_heat_capacity(t).unwrap())
+                .abs()
+                < 1e-9
+        );
+        assert!((argon.density(t).unwrap() - custom.density(t).unwrap()).abs() < 1e-9);
+        assert!((argon.density(t).unwrap() - 1.72).abs() < 1e-2);
+
+        // A mixture lies between its components
+        let (air, mixture) = (gas("air"), gas("argon and air"));
+        let mass = 0.9 * 39.948 + 0.1 * 28.97;
+        assert!((mixture.mean_molecular_mass().unwrap() - mass).abs() < 1e-6);
+        let k = mixture.thermal_conductivity(t).unwrap();
+        assert!(k > argon.thermal_conductivity(t).unwrap());
+        assert!(k < air.thermal_conductivity(t).unwrap());
+        let mu = mixture.dynamic_viscosity(t).unwrap();
+        assert!(mu > air.dynamic_viscosity(t).unwrap());
+        assert!(mu < argon.dynamic_viscosity(t).unwrap());
+        // Reference value of ISO 15099 for 90% argon and 10% air at 10C,
+        // which differs from a linear interpolation (i.e., 0.01766)
+        assert!((k - 0.01760).abs() < 2e-5, "found {}", k);
+        let cp = mixture.specific_heat_capacity(t).unwrap();
+        assert!(cp > argon.specific_heat_capacity(t).unwrap());
+        assert!(cp < air.specific_heat_capacity(t).unwrap());
+
+        // Wrong number of coefficients
+        assert!(matches!(
+            gas("bad custom").thermal_conductivity(t),
+            Err(SimpleModelError::InvalidProperty { .. })
+        ));
+    }
 }
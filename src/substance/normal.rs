@@ -68,7 +68,7 @@ pub struct Normal {
 impl Normal {
     /// Calculates the thermal diffusivity of the
     /// Normal
-    pub fn thermal_diffusivity(&self) -> Result<Float, String> {
+    pub fn thermal_diffusivity(&self) -> Result<Float, crate::SimpleModelError> {
         let thermal_conductivity = self.thermal_conductivity()?;
         let density = self.density()?;
         let specific_heat_capacity = self.specific_heat_capacity()?;
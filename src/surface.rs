@@ -26,11 +26,13 @@ use derive::{ObjectAPI, ObjectIO};
 
 use crate::{Boundary, Construction, SimpleModel};
 
-use crate::simulation_state_element::StateElementField;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
 
 /// A fixed surface in the building (or surroundings). This can be of
 /// any Construction, transparent or not.
 #[derive(ObjectIO, ObjectAPI, Clone)]
+#[api_methods(area)]
 pub struct Surface {
     /// The name of the surface
     pub name: String,
@@ -96,6 +98,57 @@ impl Surface {
     pub fn area(&self) -> Float {
         self.vertices.area()
     }
+
+    /// Pushes the physical states of the [`Surface`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// The temperatures of the nodes are not included, as their number
+    /// depends on how each simulator discretizes the construction
+    pub(crate) fn push_physical_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Surface has not been added to the model");
+        let state_index = state.push(
+            SimulationStateElement::SurfaceFrontConvectionCoefficient(obj_index),
+            0.,
+        );
+        self.set_front_convection_coefficient_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceBackConvectionCoefficient(obj_index),
+            0.,
+        );
+        self.set_back_convection_coefficient_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceFrontConvectiveHeatFlow(obj_index),
+            0.,
+        );
+        self.set_front_convective_heat_flow_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceBackConvectiveHeatFlow(obj_index),
+            0.,
+        );
+        self.set_back_convective_heat_flow_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceFrontSolarIrradiance(obj_index),
+            0.,
+        );
+        self.set_front_incident_solar_irradiance_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceBackSolarIrradiance(obj_index),
+            0.,
+        );
+        self.set_back_incident_solar_irradiance_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceFrontIRIrradiance(obj_index),
+            0.,
+        );
+        self.set_front_ir_irradiance_index(state_index);
+        let state_index = state.push(
+            SimulationStateElement::SurfaceBackIRIrradiance(obj_index),
+            0.,
+        );
+        self.set_back_ir_irradiance_index(state_index);
+    }
 }
 
 impl SimpleModel {
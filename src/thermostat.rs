@@ -0,0 +1,291 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::Space;
+use crate::{Float, SimpleModelError};
+use ::schedule::Schedule as _;
+use calendar::Date;
+use derive::{ObjectAPI, ObjectIO};
+
+use std::rc::Rc;
+
+/// A Thermostat, which holds the heating and cooling setpoints
+/// of one or more [`Space`]s.
+///
+/// Each setpoint can be constant or follow a [`Schedule`]. If both
+/// are given, the `Schedule` is used. `HVAC` systems linked to a
+/// `Thermostat` can be controlled by a simulator without the need
+/// of a control script.
+///
+/// ### Example
+///
+/// ```rs
+/// Thermostat {
+///     name: "Office thermostat",
+///     target_spaces: ["Office"],
+///     heating_setpoint: 20,
+///     cooling_setpoint_schedule: "Office cooling"
+/// }
+/// ```
+#[derive(ObjectIO, ObjectAPI, Clone)]
+#[api_methods(has_heating, has_cooling)]
+pub struct Thermostat {
+    /// The name of the Thermostat
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The `Space`s whose temperature is controlled by this
+    /// `Thermostat`
+    pub target_spaces: Vec<Rc<Space>>,
+
+    /// A constant heating setpoint, in C
+    heating_setpoint: Option<Float>,
+
+    /// A constant cooling setpoint, in C
+    cooling_setpoint: Option<Float>,
+
+    /// The heating setpoint over time, in C
+    heating_setpoint_schedule: Option<Rc<Schedule>>,
+
+    /// The cooling setpoint over time, in C
+    cooling_setpoint_schedule: Option<Rc<Schedule>>,
+
+    /// The index of the current heating setpoint
+    /// in the State array
+    #[operational("heating_setpoint")]
+    current_heating_setpoint: StateElementField,
+
+    /// The index of the current cooling setpoint
+    /// in the State array
+    #[operational("cooling_setpoint")]
+    current_cooling_setpoint: StateElementField,
+}
+
+/// Gets the value of a setpoint that might be scheduled or constant
+fn setpoint_at(
+    schedule: Result<&Rc<Schedule>, SimpleModelError>,
+    constant: Result<&Float, SimpleModelError>,
+    date: Date,
+) -> Result<Float, SimpleModelError> {
+    match schedule {
+        Ok(schedule) => Ok(schedule.get(date)),
+        Err(_) => constant.copied(),
+    }
+}
+
+impl Thermostat {
+    /// Checks whether the [`Thermostat`] has a heating setpoint
+    pub fn has_heating(&self) -> bool {
+        self.heating_setpoint_schedule().is_ok() || self.heating_setpoint().is_ok()
+    }
+
+    /// Checks whether the [`Thermostat`] has a cooling setpoint
+    pub fn has_cooling(&self) -> bool {
+        self.cooling_setpoint_schedule().is_ok() || self.cooling_setpoint().is_ok()
+    }
+
+    /// Gets the heating setpoint at a certain [`Date`]
+    pub fn heating_setpoint_at(&self, date: Date) -> Result<Float, SimpleModelError> {
+        setpoint_at(
+            self.heating_setpoint_schedule(),
+            self.heating_setpoint(),
+            date,
+        )
+    }
+
+    /// Gets the cooling setpoint at a certain [`Date`]
+    pub fn cooling_setpoint_at(&self, date: Date) -> Result<Float, SimpleModelError> {
+        setpoint_at(
+            self.cooling_setpoint_schedule(),
+            self.cooling_setpoint(),
+            date,
+        )
+    }
+
+    /// Checks that the [`Thermostat`] has at least one setpoint, and that
+    /// its constant heating setpoint is not above its constant cooling setpoint
+    pub fn check(&self) -> Result<(), SimpleModelError> {
+        let err = |message: String| {
+            Err(SimpleModelError::InvalidProperty {
+                object_type: "Thermostat".to_string(),
+                object_name: self.name.clone(),
+                message,
+            })
+        };
+        if !self.has_heating() && !self.has_cooling() {
+            return err("it has neither a heating nor a cooling setpoint".to_string());
+        }
+        if let (Ok(heating), Ok(cooling)) = (self.heating_setpoint(), self.cooling_setpoint()) {
+            if heating > cooling {
+                return err(format!(
+                    "its heating setpoint ({}) is higher than its cooling setpoint ({})",
+                    heating, cooling
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Pushes the operational states of the [`Thermostat`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// The heating and cooling setpoint states are only
+    /// pushed if the `Thermostat` has such setpoints. Their initial
+    /// values are those of the first hour of the year.
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Thermostat has not been added to the model");
+        let start = Date {
+            month: 1,
+            day: 1,
+            hour: 0.,
+        };
+        if self.has_heating() {
+            let initial = self.heating_setpoint_at(start).unwrap_or(0.);
+            let state_index = state.push(
+                SimulationStateElement::ThermostatHeatingSetpoint(obj_index),
+                initial,
+            );
+            self.set_current_heating_setpoint_index(state_index);
+        }
+        if self.has_cooling() {
+            let initial = self.cooling_setpoint_at(start).unwrap_or(0.);
+            let state_index = state.push(
+                SimulationStateElement::ThermostatCoolingSetpoint(obj_index),
+                initial,
+            );
+            self.set_current_cooling_setpoint_index(state_index);
+        }
+    }
+}
+
+impl SimpleModel {
+    /// Adds a [`Thermostat`] to the [`SimpleModel`]
+    pub fn add_thermostat(
+        &mut self,
+        mut add: Thermostat,
+        state: &mut SimulationStateHeader,
+    ) -> Rc<Thermostat> {
+        // Check the index of this object
+        let obj_index = self.thermostats.len();
+        add.set_index(obj_index);
+
+        // Push the states, and map into the object
+        add.push_operational_states(state);
+
+        // Add to model, and return a reference
+        let add = Rc::new(add);
+        self.thermostats.push(Rc::clone(&add));
+        add
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::hvac::HVAC;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_thermostat() {
+        let src = b"
+Schedule {
+    name: \"cooling\",
+    profile: ScheduleProfile::Daily([
+        30, 30, 30, 30, 30, 30, 30, 30, 25, 25, 25, 25,
+        25, 25, 25, 25, 25, 25, 30, 30, 30, 30, 30, 30
+    ])
+}
+
+Space { name: \"office\" }
+
+HVAC::IdealHeaterCooler {
+    name: \"the hvac\",
+    target_spaces: [\"office\"],
+    thermostat: \"the thermostat\"
+}
+
+Thermostat {
+    name: \"the thermostat\",
+    target_spaces: [\"office\"],
+    heating_setpoint: 20,
+    cooling_setpoint: 24,
+    cooling_setpoint_schedule: \"cooling\"
+}
+
+Thermostat {
+    name: \"heating only\",
+    target_spaces: [\"office\"],
+    heating_setpoint: 18
+}
+
+Thermostat {
+    name: \"nothing\",
+    target_spaces: [\"office\"]
+}
+";
+        let (model, mut header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.thermostats.len(), 3);
+
+        // Scheduled values have priority over constant ones
+        let thermostat = &model.thermostats[0];
+        let date = |hour| Date {
+            month: 3,
+            day: 2,
+            hour,
+        };
+        assert_eq!(thermostat.heating_setpoint_at(date(3.)).unwrap(), 20.);
+        assert_eq!(thermostat.cooling_setpoint_at(date(3.)).unwrap(), 30.);
+        assert_eq!(thermostat.cooling_setpoint_at(date(12.)).unwrap(), 25.);
+        assert!(thermostat.check().is_ok());
+
+        // The HVAC is linked to it
+        if let HVAC::IdealHeaterCooler(hvac) = &model.hvacs[0] {
+            assert!(Rc::ptr_eq(hvac.thermostat().unwrap(), thermostat));
+        } else {
+            panic!("Expecting an IdealHeaterCooler");
+        }
+
+        // Only the setpoints that exist are in the state
+        let heating_only = &model.thermostats[1];
+        assert!(heating_only.cooling_setpoint_at(date(3.)).is_err());
+        assert!(heating_only.current_cooling_setpoint_index().is_none());
+        assert!(model.thermostats[2].check().is_err());
+        assert_eq!(header.n_operational(), 4); // 3 setpoints + the HVAC
+
+        let mut state = header.take_values().unwrap();
+        assert_eq!(thermostat.current_heating_setpoint(&state), Some(20.));
+        assert_eq!(thermostat.current_cooling_setpoint(&state), Some(30.));
+        heating_only.set_current_heating_setpoint(&mut state, 21.);
+        assert_eq!(heating_only.current_heating_setpoint(&state), Some(21.));
+        assert_eq!(heating_only.current_cooling_setpoint(&state), None);
+    }
+}
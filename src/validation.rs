@@ -0,0 +1,557 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::hvac::HVAC;
+use crate::{Float, Infiltration, SimpleModel, SimpleModelError, Space, Substance};
+use geometry3d::Polygon3D;
+
+/// The maximum distance (in meters) between a vertex of a polygon
+/// and the plane of that polygon for it to be considered planar
+const PLANARITY_TOLERANCE: Float = 1e-3;
+
+/// The minimum area (in m2) of a polygon that is not degenerate
+const AREA_TOLERANCE: Float = 1e-6;
+
+/// A problem found by [`SimpleModel::validate`].
+///
+/// Contrary to [`SimpleModelError`](crate::SimpleModelError), these do
+/// not prevent building the model, but they are likely to make simulations
+/// fail or produce meaningless results.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// Two or more objects of the same type share a name
+    DuplicateName {
+        object_type: String,
+        object_name: String,
+    },
+
+    /// A `Material` whose thickness is zero or negative
+    NonPositiveThickness { material: String, thickness: Float },
+
+    /// A `Normal` substance that lacks a property needed for
+    /// thermal simulation
+    MissingSubstanceProperty { substance: String, property: String },
+
+    /// A polygon that does not enclose any area (e.g., it has collinear vertices)
+    DegeneratePolygon {
+        object_type: String,
+        object_name: String,
+    },
+
+    /// A polygon whose vertices are not all in the same plane
+    NonPlanarPolygon {
+        object_type: String,
+        object_name: String,
+        /// The largest distance between a vertex and the plane of the polygon
+        max_deviation: Float,
+    },
+
+    /// An `HVAC`, a `Thermostat`, a `Ventilation`, a `Luminaire` or an
+    /// `Equipment` that is not associated to any `Space`
+    NoTargetSpace {
+        object_type: String,
+        object_name: String,
+    },
+
+    /// A `Space` with no volume
+    NoSpaceVolume { space: String },
+
+    /// A `Space` with an `EffectiveAirLeakageArea` infiltration, whose
+    /// `Building` does not have the data needed for calculating the
+    /// stack and wind coefficients
+    MissingInfiltrationData { space: String, message: String },
+
+    /// A `Thermostat` without setpoints, or whose heating setpoint
+    /// is higher than its cooling setpoint
+    InvalidThermostat { thermostat: String, message: String },
+
+    /// An `AirExchange` that connects a `Space` with itself, or whose
+    /// `Fenestration` is not an operable door between its spaces
+    InvalidAirExchange {
+        air_exchange: String,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateName {
+                object_type,
+                object_name,
+            } => write!(
+                f,
+                "There is more than one {} called '{}'",
+                object_type, object_name
+            ),
+            Self::NonPositiveThickness {
+                material,
+                thickness,
+            } => write!(
+                f,
+                "Material '{}' has a thickness of {}... it must be positive",
+                material, thickness
+            ),
+            Self::MissingSubstanceProperty {
+                substance,
+                property,
+            } => write!(
+                f,
+                "Substance '{}' has no '{}', which is needed for thermal simulation",
+                substance, property
+            ),
+            Self::DegeneratePolygon {
+                object_type,
+                object_name,
+            } => write!(
+                f,
+                "The vertices of {} '{}' do not enclose any area",
+                object_type, object_name
+            ),
+            Self::NonPlanarPolygon {
+                object_type,
+                object_name,
+                max_deviation,
+            } => write!(
+                f,
+                "The vertices of {} '{}' are not planar (a vertex is {}m away from its plane)",
+                object_type, object_name, max_deviation
+            ),
+            Self::NoTargetSpace {
+                object_type,
+                object_name,
+            } => write!(
+                f,
+                "{} '{}' is not associated to any Space",
+                object_type, object_name
+            ),
+            Self::NoSpaceVolume { space } => write!(f, "Space '{}' has no volume", space),
+            Self::MissingInfiltrationData { space, message } => write!(
+                f,
+                "Space '{}' has an EffectiveAirLeakageArea infiltration, but {}",
+                space, message
+            ),
+            Self::InvalidThermostat {
+                thermostat,
+                message,
+            } => write!(f, "Thermostat '{}' is invalid: {}", thermostat, message),
+            Self::InvalidAirExchange {
+                air_exchange,
+                message,
+            } => write!(f, "AirExchange '{}' is invalid: {}", air_exchange, message),
+        }
+    }
+}
+
+/// Checks whether there are repeated names in a collection
+fn check_duplicates<'a, T: Iterator<Item = &'a String>>(
+    object_type: &str,
+    names: T,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let mut seen: Vec<&String> = Vec::new();
+    let mut reported: Vec<&String> = Vec::new();
+    for name in names {
+        if seen.contains(&name) {
+            if !reported.contains(&name) {
+                issues.push(ValidationIssue::DuplicateName {
+                    object_type: object_type.to_string(),
+                    object_name: name.clone(),
+                });
+                reported.push(name);
+            }
+        } else {
+            seen.push(name);
+        }
+    }
+}
+
+/// Checks that a polygon is planar and encloses some area
+fn check_polygon(
+    object_type: &str,
+    object_name: &str,
+    polygon: &Polygon3D,
+    issues: &mut Vec<ValidationIssue>,
+) {
+    let area = polygon.area();
+    if polygon.outer().n_vertices() < 3 || !area.is_finite() || area < AREA_TOLERANCE {
+        issues.push(ValidationIssue::DegeneratePolygon {
+            object_type: object_type.to_string(),
+            object_name: object_name.to_string(),
+        });
+        return;
+    }
+
+    let normal = polygon.normal();
+    let vertices = polygon.outer().vertices();
+    let first = vertices[0];
+    let max_deviation = vertices
+        .iter()
+        .map(|v| ((*v - first) * normal).abs())
+        .fold(0.0, Float::max);
+    if max_deviation > PLANARITY_TOLERANCE {
+        issues.push(ValidationIssue::NonPlanarPolygon {
+            object_type: object_type.to_string(),
+            object_name: object_name.to_string(),
+            max_deviation,
+        });
+    }
+}
+
+/// Checks that the `Building` of a `Space` with an `EffectiveAirLeakageArea`
+/// allows calculating the stack and wind coefficients
+fn check_infiltration(space: &Space, issues: &mut Vec<ValidationIssue>) {
+    if !matches!(
+        space.infiltration(),
+        Ok(Infiltration::EffectiveAirLeakageArea(_))
+    ) {
+        return;
+    }
+
+    let message = match space.building() {
+        Err(_) => Some("it is not associated to any Building".to_string()),
+        Ok(building) => {
+            let has_stack = building.stack_coefficient().is_ok() || building.n_storeys().is_ok();
+            let has_wind = building.wind_coefficient().is_ok()
+                || (building.n_storeys().is_ok() && building.shelter_class().is_ok());
+            if has_stack && has_wind {
+                None
+            } else {
+                Some(format!(
+                    "Building '{}' needs 'n_storeys' and 'shelter_class', or 'stack_coefficient' and 'wind_coefficient'",
+                    building.name()
+                ))
+            }
+        }
+    };
+
+    if let Some(message) = message {
+        issues.push(ValidationIssue::MissingInfiltrationData {
+            space: space.name.clone(),
+            message,
+        });
+    }
+}
+
+impl SimpleModel {
+    /// Checks the [`SimpleModel`] for problems that do not prevent
+    /// building it, but that are likely to make simulations fail or
+    /// produce meaningless results. An empty `Vec` means that no
+    /// issues were found.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        // Names should be unique
+        check_duplicates(
+            "Substance",
+            self.substances.iter().map(|s| s.name()),
+            &mut issues,
+        );
+        check_duplicates(
+            "Material",
+            self.materials.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "Construction",
+            self.constructions.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "Surface",
+            self.surfaces.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "Schedule",
+            self.schedules.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates("Space", self.spaces.iter().map(|s| &s.name), &mut issues);
+        check_duplicates(
+            "Building",
+            self.buildings.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "Fenestration",
+            self.fenestrations.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates("HVAC", self.hvacs.iter().map(|s| s.name()), &mut issues);
+        check_duplicates(
+            "Luminaire",
+            self.luminaires.iter().map(|s| s.name()),
+            &mut issues,
+        );
+        check_duplicates(
+            "Thermostat",
+            self.thermostats.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "Ventilation",
+            self.ventilations.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "AirExchange",
+            self.air_exchanges.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "Equipment",
+            self.equipment.iter().map(|s| s.name()),
+            &mut issues,
+        );
+        check_duplicates(
+            "Occupant",
+            self.occupants.iter().map(|s| &s.name),
+            &mut issues,
+        );
+        check_duplicates(
+            "ShadingDevice",
+            self.shading_devices.iter().map(|s| &s.name),
+            &mut issues,
+        );
+
+        // Substances
+        for substance in &self.substances {
+            if let Substance::Normal(s) = substance {
+                let properties = [
+                    ("thermal_conductivity", s.thermal_conductivity().is_ok()),
+                    ("specific_heat_capacity", s.specific_heat_capacity().is_ok()),
+                    ("density", s.density().is_ok()),
+                ];
+                for (property, is_there) in properties {
+                    if !is_there {
+                        issues.push(ValidationIssue::MissingSubstanceProperty {
+                            substance: s.name.clone(),
+                            property: property.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // Thermostats
+        for thermostat in &self.thermostats {
+            if let Err(SimpleModelError::InvalidProperty { message, .. }) = thermostat.check() {
+                issues.push(ValidationIssue::InvalidThermostat {
+                    thermostat: thermostat.name.clone(),
+                    message,
+                });
+            }
+        }
+
+        // Air exchanges
+        for air_exchange in &self.air_exchanges {
+            if let Err(SimpleModelError::InvalidProperty { message, .. }) = air_exchange.check() {
+                issues.push(ValidationIssue::InvalidAirExchange {
+                    air_exchange: air_exchange.name.clone(),
+                    message,
+                });
+            }
+        }
+
+        // Materials
+        for material in &self.materials {
+            if material.thickness <= 0.0 {
+                issues.push(ValidationIssue::NonPositiveThickness {
+                    material: material.name.clone(),
+                    thickness: material.thickness,
+                });
+            }
+        }
+
+        // Geometry
+        for surface in &self.surfaces {
+            check_polygon("Surface", &surface.name, &surface.vertices, &mut issues);
+        }
+        for fenestration in &self.fenestrations {
+            check_polygon(
+                "Fenestration",
+                &fenestration.name,
+                &fenestration.vertices,
+                &mut issues,
+            );
+        }
+
+        // Spaces
+        for space in &self.spaces {
+            match space.volume() {
+                Ok(v) if v > 0.0 => {}
+                _ => issues.push(ValidationIssue::NoSpaceVolume {
+                    space: space.name.clone(),
+                }),
+            }
+            check_infiltration(space, &mut issues);
+        }
+
+        // Things that need to be in a space
+        for hvac in &self.hvacs {
+            let has_space = match hvac {
+                HVAC::ElectricHeater(h) => h.target_space().is_ok(),
+                HVAC::IdealHeaterCooler(h) => !h.target_spaces.is_empty(),
+                HVAC::AirSourceHeatPump(h) => h.target_space().is_ok(),
+                HVAC::GasBoiler(h) => !h.target_spaces.is_empty(),
+                HVAC::FanCoil(h) => !h.target_spaces.is_empty(),
+            };
+            if !has_space {
+                issues.push(ValidationIssue::NoTargetSpace {
+                    object_type: "HVAC".to_string(),
+                    object_name: hvac.name().clone(),
+                });
+            }
+        }
+        for thermostat in &self.thermostats {
+            if thermostat.target_spaces.is_empty() {
+                issues.push(ValidationIssue::NoTargetSpace {
+                    object_type: "Thermostat".to_string(),
+                    object_name: thermostat.name.clone(),
+                });
+            }
+        }
+        for ventilation in &self.ventilations {
+            if ventilation.target_spaces.is_empty() {
+                issues.push(ValidationIssue::NoTargetSpace {
+                    object_type: "Ventilation".to_string(),
+                    object_name: ventilation.name.clone(),
+                });
+            }
+        }
+        for luminaire in &self.luminaires {
+            if luminaire.target_space().is_err() {
+                issues.push(ValidationIssue::NoTargetSpace {
+                    object_type: "Luminaire".to_string(),
+                    object_name: luminaire.name().clone(),
+                });
+            }
+        }
+        for equipment in &self.equipment {
+            if equipment.target_space().is_err() {
+                issues.push(ValidationIssue::NoTargetSpace {
+                    object_type: "Equipment".to_string(),
+                    object_name: equipment.name().clone(),
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_validate() {
+        let src = b"
+Building {
+    name: \"the building\",
+    n_storeys: 2
+}
+
+Substance::Normal {
+    name: \"the substance\",
+    thermal_conductivity: 1.2,
+    density: 2000
+}
+
+Material {
+    name: \"the material\",
+    substance: \"the substance\",
+    thickness: 0
+}
+
+Material {
+    name: \"the material\",
+    substance: \"the substance\",
+    thickness: 0.1
+}
+
+Construction {
+    name: \"the construction\",
+    materials: [\"the material\"]
+}
+
+Space {
+    name: \"the space\",
+    building: \"the building\",
+    infiltration: Infiltration::EffectiveAirLeakageArea(300)
+}
+
+Surface {
+    name: \"the surface\",
+    construction: \"the construction\",
+    vertices: [0, 0, 0, 1, 0, 0, 1, 1, 0.5, 0, 1, 0]
+}
+
+Luminaire {
+    name: \"the luminaire\"
+}
+";
+        let (model, _header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        let issues = model.validate();
+
+        assert_eq!(
+            issues,
+            vec![
+                ValidationIssue::DuplicateName {
+                    object_type: "Material".to_string(),
+                    object_name: "the material".to_string()
+                },
+                ValidationIssue::MissingSubstanceProperty {
+                    substance: "the substance".to_string(),
+                    property: "specific_heat_capacity".to_string()
+                },
+                ValidationIssue::NonPositiveThickness {
+                    material: "the material".to_string(),
+                    thickness: 0.0
+                },
+                ValidationIssue::NonPlanarPolygon {
+                    object_type: "Surface".to_string(),
+                    object_name: "the surface".to_string(),
+                    max_deviation: match &issues[3] {
+                        ValidationIssue::NonPlanarPolygon { max_deviation, .. } => *max_deviation,
+                        _ => panic!("Expecting a NonPlanarPolygon"),
+                    }
+                },
+                ValidationIssue::NoSpaceVolume {
+                    space: "the space".to_string()
+                },
+                ValidationIssue::MissingInfiltrationData {
+                    space: "the space".to_string(),
+                    message: "Building 'the building' needs 'n_storeys' and 'shelter_class', or 'stack_coefficient' and 'wind_coefficient'".to_string()
+                },
+                ValidationIssue::NoTargetSpace {
+                    object_type: "Luminaire".to_string(),
+                    object_name: "the luminaire".to_string()
+                },
+            ]
+        );
+    }
+}
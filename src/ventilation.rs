@@ -0,0 +1,255 @@
+/*
+MIT License
+Copyright (c) 2021 Germán Molina
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+*/
+
+use crate::model::SimpleModel;
+use crate::schedule::Schedule;
+use crate::simulation_state::SimulationStateHeader;
+use crate::simulation_state_element::{SimulationStateElement, StateElementField};
+use crate::space::{Space, INITIAL_AIR_TEMPERATURE};
+use crate::{Float, SimpleModelError};
+use derive::{ObjectAPI, ObjectIO};
+
+use std::rc::Rc;
+
+/// A mechanical ventilation system, which supplies air
+/// to—and exhausts air from—one or more [`Space`]s
+///
+/// ### Example
+///
+/// ```rs
+/// Ventilation {
+///     name: "Office ventilation",
+///     target_spaces: ["Office 1", "Office 2"],
+///     supply_flow: 0.3,
+///     exhaust_flow: 0.3,
+///     heat_recovery_efficiency: 0.75,
+///     availability: "Office hours"
+/// }
+/// ```
+#[derive(ObjectIO, ObjectAPI, Clone)]
+pub struct Ventilation {
+    /// The name of the Ventilation
+    pub name: String,
+
+    /// The position of this object in its contaner Vector
+    index: Option<usize>,
+
+    /// The `Space`s that this `Ventilation` serves
+    pub target_spaces: Vec<Rc<Space>>,
+
+    /// The design volume of air supplied, in m3/s
+    supply_flow: Option<Float>,
+
+    /// The design volume of air exhausted, in m3/s
+    exhaust_flow: Option<Float>,
+
+    /// The fraction of the temperature difference between
+    /// the exhausted and the outdoor air that is recovered
+    /// by the supply air (from 0 to 1). Defaults to 0.
+    heat_recovery_efficiency: Option<Float>,
+
+    /// The fraction of the `supply_flow` that is available
+    /// over time (from 0 to 1)
+    availability: Option<Rc<Schedule>>,
+
+    /// The index of the current volume of air supplied
+    /// in the State array
+    #[operational]
+    flow_rate: StateElementField,
+}
+
+impl Ventilation {
+    /// Calculates the temperature of the supply air, given the temperatures
+    /// of the outdoor air and of the air being exhausted
+    pub fn supply_temperature(
+        &self,
+        outdoor_temperature: Float,
+        exhaust_temperature: Float,
+    ) -> Result<Float, SimpleModelError> {
+        let efficiency = self.heat_recovery_efficiency().copied().unwrap_or(0.0);
+        if !(0.0..=1.0).contains(&efficiency) {
+            return Err(SimpleModelError::InvalidProperty {
+                object_type: "Ventilation".to_string(),
+                object_name: self.name.clone(),
+                message: format!(
+                    "heat_recovery_efficiency must be between 0 and 1... found {}",
+                    efficiency
+                ),
+            });
+        }
+        Ok(outdoor_temperature + efficiency * (exhaust_temperature - outdoor_temperature))
+    }
+
+    /// Pushes the operational states of the [`Ventilation`] into
+    /// a [`SimulationStateHeader`], and maps them into the object.
+    ///
+    /// The ventilation states of the target [`Space`]s are not included
+    pub(crate) fn push_operational_states(&self, state: &mut SimulationStateHeader) {
+        let obj_index = *self
+            .index()
+            .expect("Ventilation has not been added to the model");
+        let flow = self.supply_flow().copied().unwrap_or(0.);
+        let state_index = state.push(SimulationStateElement::VentilationFlowRate(obj_index), flow);
+        self.set_flow_rate_index(state_index);
+    }
+}
+
+impl SimpleModel {
+    /// Adds a [`Ventilation`] to the [`SimpleModel`]
+    ///
+    /// Besides the flow rate of the `Ventilation`, this registers the
+    /// ventilation volume and temperature of its target [`Space`]s, unless
+    /// they had been registered already (e.g., by another `Ventilation`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a target [`Space`] has not been added to the model (i.e.,
+    /// it has no index)
+    pub fn add_ventilation(
+        &mut self,
+        mut add: Ventilation,
+        state: &mut SimulationStateHeader,
+    ) -> Rc<Ventilation> {
+        // Check the index of this object
+        let obj_index = self.ventilations.len();
+        add.set_index(obj_index);
+
+        // Push the states, and map into the object
+        add.push_operational_states(state);
+
+        for space in add.target_spaces.iter() {
+            let space_index = *space
+                .index()
+                .expect("Target Space of Ventilation has not been added to the model");
+            if space.ventilation_volume_index().is_none() {
+                let state_index = state.push(
+                    SimulationStateElement::SpaceVentilationVolume(space_index),
+                    0.,
+                );
+                space.set_ventilation_volume_index(state_index);
+            }
+            if space.ventilation_temperature_index().is_none() {
+                let state_index = state.push(
+                    SimulationStateElement::SpaceVentilationTemperature(space_index),
+                    INITIAL_AIR_TEMPERATURE,
+                );
+                space.set_ventilation_temperature_index(state_index);
+            }
+        }
+
+        // Add to model, and return a reference
+        let add = Rc::new(add);
+        self.ventilations.push(Rc::clone(&add));
+        add
+    }
+}
+
+/***********/
+/* TESTING */
+/***********/
+
+#[cfg(test)]
+mod testing {
+    use super::*;
+    use crate::scanner::SimpleScanner;
+
+    #[test]
+    fn test_ventilation() {
+        let src = b"
+Space { name: \"office 1\" }
+Space { name: \"office 2\" }
+
+Ventilation {
+    name: \"the ventilation\",
+    target_spaces: [\"office 1\", \"office 2\"],
+    supply_flow: 0.3,
+    exhaust_flow: 0.25,
+    heat_recovery_efficiency: 0.75
+}
+
+Ventilation {
+    name: \"extra ventilation\",
+    target_spaces: [\"office 2\"]
+}
+";
+        let (model, mut header) = SimpleScanner::new(src, 1).parse_model().unwrap();
+        assert_eq!(model.ventilations.len(), 2);
+
+        let ventilation = &model.ventilations[0];
+        assert_eq!(*ventilation.exhaust_flow().unwrap(), 0.25);
+        assert!((ventilation.supply_temperature(0., 20.).unwrap() - 15.).abs() < 1e-9);
+        assert_eq!(
+            model.ventilations[1].supply_temperature(0., 20.).unwrap(),
+            0.
+        );
+
+        // The flow rates are operational, and the spaces are physical
+        let ventilation_elements: Vec<SimulationStateElement> = header
+            .elements
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    SimulationStateElement::VentilationFlowRate(_)
+                        | SimulationStateElement::SpaceVentilationVolume(_)
+                        | SimulationStateElement::SpaceVentilationTemperature(_)
+                )
+            })
+            .copied()
+            .collect();
+        assert_eq!(
+            ventilation_elements,
+            vec![
+                SimulationStateElement::VentilationFlowRate(0),
+                SimulationStateElement::VentilationFlowRate(1),
+                SimulationStateElement::SpaceVentilationVolume(0),
+                SimulationStateElement::SpaceVentilationTemperature(0),
+                SimulationStateElement::SpaceVentilationVolume(1),
+                SimulationStateElement::SpaceVentilationTemperature(1),
+            ]
+        );
+
+        // When adding them one by one, the spaces are registered only once
+        let mut other_model = SimpleModel::new("other model".to_string());
+        let mut other_header = SimulationStateHeader::new();
+        let space = other_model.add_space(Space::new("the space".to_string()));
+        for name in ["first", "second"] {
+            let mut add = Ventilation::new(name.to_string());
+            add.target_spaces.push(Rc::clone(&space));
+            other_model.add_ventilation(add, &mut other_header);
+        }
+        assert_eq!(
+            other_header.elements,
+            vec![
+                SimulationStateElement::VentilationFlowRate(0),
+                SimulationStateElement::SpaceVentilationVolume(0),
+                SimulationStateElement::SpaceVentilationTemperature(0),
+                SimulationStateElement::VentilationFlowRate(1),
+            ]
+        );
+
+        let mut state = header.take_values().unwrap();
+        assert_eq!(ventilation.flow_rate(&state), Some(0.3));
+        ventilation.set_flow_rate(&mut state, 0.1);
+        assert_eq!(ventilation.flow_rate(&state), Some(0.1));
+        model.spaces[1].set_ventilation_volume(&mut state, 0.2);
+        assert_eq!(model.spaces[1].ventilation_volume(&state), Some(0.2));
+    }
+}